@@ -0,0 +1,181 @@
+use std::marker::PhantomData;
+
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+
+use crate::field::Field;
+use crate::query::{Params, escape_field};
+use crate::{Model, Query, Result};
+
+/// Aggregated value of the records sharing the same `key`
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Group<K, V> {
+    pub key: K,
+    pub value: V,
+}
+
+#[derive(Deserialize)]
+struct Aggregate<V> {
+    value: V,
+}
+
+impl<'c, T> Query<'c, T>
+where
+    T: Model,
+{
+    /// Group matching records by `field`, ignoring ordering and pagination
+    ///
+    /// ```ignore
+    /// let per_status = Task::objects(db)
+    ///     .filter(Task::fields.project_id.eq(project.id))
+    ///     .group_by(Task::fields.status)
+    ///     .count()
+    ///     .await?;
+    /// ```
+    pub fn group_by<K>(self, field: impl Into<Field<T, K>>) -> GroupBy<'c, T, K> {
+        GroupBy {
+            query: self,
+            key: field.into().name(),
+            _key: PhantomData,
+        }
+    }
+
+    /// Sum `field` over matching records, zero when nothing matches
+    pub async fn sum<V>(&self, field: Field<T, V>) -> Result<V>
+    where
+        V: DeserializeOwned + Default,
+    {
+        let value = self
+            .aggregate(&format!("math::sum({})", escape_field(field.name())))
+            .await?;
+        Ok(value.unwrap_or_default())
+    }
+
+    /// Average `field` over matching records, `None` when nothing matches
+    pub async fn mean<V>(&self, field: Field<T, V>) -> Result<Option<f64>> {
+        self.aggregate(&format!("math::mean({})", escape_field(field.name())))
+            .await
+    }
+
+    pub(crate) fn render_aggregate(
+        &self,
+        expr: &str,
+        key: Option<&str>,
+        params: &mut Params,
+    ) -> String {
+        params.insert("table", T::TABLE_NAME);
+        let (select, group) = match key {
+            Some(key) => (format!("{} AS key, ", escape_field(key)), "BY key"),
+            None => (String::new(), "ALL"),
+        };
+        format!(
+            "SELECT {}{} AS value FROM type::table($table){} GROUP {}",
+            select,
+            expr,
+            self.render_where(params),
+            group
+        )
+    }
+
+    async fn aggregate<V: DeserializeOwned>(&self, expr: &str) -> Result<Option<V>> {
+        let mut params = Params::default();
+        let sql = self.render_aggregate(expr, None, &mut params);
+        let aggregate: Option<Aggregate<V>> = self
+            .client
+            .query(sql)
+            .bind(params.into_bindings()?)
+            .await?
+            .take(0)?;
+        Ok(aggregate.map(|aggregate| aggregate.value))
+    }
+}
+
+/// A [`Query`] aggregating matching records per value of a field
+pub struct GroupBy<'c, T, K> {
+    query: Query<'c, T>,
+    key: &'static str,
+    _key: PhantomData<fn() -> K>,
+}
+
+impl<T, K> GroupBy<'_, T, K>
+where
+    T: Model,
+    K: DeserializeOwned,
+{
+    /// Number of records in each group
+    pub async fn count(&self) -> Result<Vec<Group<K, u64>>> {
+        self.aggregate("count()").await
+    }
+
+    /// Sum of `field` in each group
+    pub async fn sum<V: DeserializeOwned>(&self, field: Field<T, V>) -> Result<Vec<Group<K, V>>> {
+        self.aggregate(&format!("math::sum({})", escape_field(field.name())))
+            .await
+    }
+
+    /// Average of `field` in each group
+    pub async fn mean<V>(&self, field: Field<T, V>) -> Result<Vec<Group<K, f64>>> {
+        self.aggregate(&format!("math::mean({})", escape_field(field.name())))
+            .await
+    }
+
+    async fn aggregate<V: DeserializeOwned>(&self, expr: &str) -> Result<Vec<Group<K, V>>> {
+        let mut params = Params::default();
+        let sql = self
+            .query
+            .render_aggregate(expr, Some(self.key), &mut params);
+        Ok(self
+            .query
+            .client
+            .query(sql)
+            .bind(params.into_bindings()?)
+            .await?
+            .take(0)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+    use crate::field::Link;
+    use crate::{Condition, Id};
+
+    #[derive(Serialize, Deserialize)]
+    struct AnyModel {}
+
+    impl Model for AnyModel {
+        const TABLE_NAME: &'static str = "any_model";
+        #[cfg(feature = "utoipa")]
+        type Data = ();
+        type Input = ();
+        type Patch = ();
+        fn table_name(&self) -> &'static str {
+            Self::TABLE_NAME
+        }
+        #[cfg(feature = "utoipa")]
+        fn into_data(self) -> Self::Data {}
+    }
+
+    #[test]
+    fn test_render_aggregate() {
+        let client = surrealdb::Surreal::init();
+        let query = Query::<AnyModel>::new(&client).filter(Condition::gt("points", 0));
+        let mut params = Params::default();
+        assert_eq!(
+            query.render_aggregate("math::sum(points)", None, &mut params),
+            "SELECT math::sum(points) AS value FROM type::table($table) WHERE points > $p0 GROUP ALL"
+        );
+
+        let owner: Link<AnyModel, AnyModel> = Link::new("owner");
+        let group = query.group_by::<Id<AnyModel>>(owner);
+        assert_eq!(group.key, "owner");
+        assert_eq!(
+            group
+                .query
+                .render_aggregate("count()", Some(group.key), &mut params),
+            "SELECT owner AS key, count() AS value FROM type::table($table) WHERE points > $p1 GROUP BY key"
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use std::{error::Error as StdError, fmt};
+
+use crate::validation::ValidationErrors;
+
+/// Prefix of messages thrown by [`Transaction::ensure`](crate::Transaction::ensure)
+pub(crate) const GUARD_PREFIX: &str = "merak:guard:";
+
+/// Message of the [`Error::Conflict`] returned when a versioned write is rejected
+pub(crate) const VERSION_CONFLICT: &str = "Record was modified by another write";
+
+/// Error returned by model operations
+///
+/// Database errors with a well-known cause are classified so that callers can
+/// react to them without matching on SurrealDB error messages.
+#[derive(Debug)]
+pub enum Error {
+    /// The record does not exist
+    NotFound,
+    /// A unique index on `field` already contains the written value
+    UniqueViolation {
+        field: String,
+    },
+    /// The write conflicts with the current state of the database
+    Conflict(String),
+    /// A value was rejected by the table schema
+    Validation(String),
+    /// Fields violate their `#[field(validate(...))]` rules
+    Invalid(ValidationErrors),
+    Database(Box<surrealdb::Error>),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound => write!(f, "Record not found"),
+            Error::UniqueViolation { field } => write!(f, "Value of `{}` already exists", field),
+            Error::Conflict(message) => write!(f, "{}", message),
+            Error::Validation(message) => write!(f, "{}", message),
+            Error::Invalid(errors) => write!(f, "Invalid fields: {}", errors),
+            Error::Database(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Database(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<ValidationErrors> for Error {
+    fn from(errors: ValidationErrors) -> Self {
+        Error::Invalid(errors)
+    }
+}
+
+impl From<surrealdb::Error> for Error {
+    fn from(err: surrealdb::Error) -> Self {
+        // Remote engines only forward the message, so classify by its text
+        let message = err.to_string();
+        if let Some(field) = unique_field(&message) {
+            return Error::UniqueViolation { field };
+        }
+        if message.contains("Database record `") && message.contains("already exists") {
+            return Error::Conflict(message);
+        }
+        if let Some(message) = guard_message(&err, &message) {
+            return Error::Conflict(message.to_string());
+        }
+        if message.contains("for field `") {
+            return Error::Validation(message);
+        }
+        if matches!(
+            err,
+            surrealdb::Error::Db(surrealdb::error::Db::IdNotFound { .. })
+        ) {
+            return Error::NotFound;
+        }
+        Error::Database(Box::new(err))
+    }
+}
+
+/// Extract the field of a unique index from an "index already contains" error
+///
+/// Indexes generated by [`TableSchema::index`](crate::schema::TableSchema::index)
+/// are named `<table>_<field>_unique`, other index names are returned as-is.
+fn unique_field(message: &str) -> Option<String> {
+    let index = between(message, "Database index `", "`")?;
+    if !message.contains("already contains") {
+        return None;
+    }
+    let table = between(message, "with record `", "`")
+        .and_then(|thing| thing.split(':').next())
+        .map(|table| table.trim_matches(['⟨', '⟩', '`']));
+    let field = table
+        .and_then(|table| index.strip_prefix(table))
+        .and_then(|rest| rest.strip_prefix('_'))
+        .and_then(|rest| rest.strip_suffix("_unique"))
+        .unwrap_or(index);
+    Some(field.to_string())
+}
+
+/// Extract the message of a guard failure
+///
+/// Only errors raised by `THROW` are guards, so that written values
+/// containing the prefix are not mistaken for one.
+fn guard_message<'a>(err: &'a surrealdb::Error, message: &'a str) -> Option<&'a str> {
+    if let surrealdb::Error::Db(surrealdb::error::Db::Thrown(thrown)) = err {
+        return thrown.strip_prefix(GUARD_PREFIX);
+    }
+    let thrown = format!("An error occurred: {}", GUARD_PREFIX);
+    let start = message.find(&thrown)? + thrown.len();
+    Some(message[start..].trim_end_matches(['\'', '"']))
+}
+
+fn between<'a>(message: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let rest = &message[message.find(start)? + start.len()..];
+    Some(&rest[..rest.find(end)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unique_violation() {
+        let message = "Database index `users_username_unique` already contains 'merak', with record `users:abc`";
+        assert_eq!(unique_field(message).as_deref(), Some("username"));
+        let message = "Database index `by_email` already contains 'merak', with record `users:abc`";
+        assert_eq!(unique_field(message).as_deref(), Some("by_email"));
+        assert_eq!(unique_field("There was a problem"), None);
+    }
+
+    #[test]
+    fn test_guard_error() {
+        let err: surrealdb::Error =
+            surrealdb::error::Db::Thrown(format!("{}Refresh token revoked", GUARD_PREFIX)).into();
+        match Error::from(err) {
+            Error::Conflict(message) => assert_eq!(message, "Refresh token revoked"),
+            err => panic!("unexpected error: {}", err),
+        }
+        let err: surrealdb::Error = surrealdb::error::Api::Query(format!(
+            "There was a problem with the database: An error occurred: {}Refresh token revoked",
+            GUARD_PREFIX
+        ))
+        .into();
+        match Error::from(err) {
+            Error::Conflict(message) => assert_eq!(message, "Refresh token revoked"),
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_guard_prefix_in_value() {
+        let err: surrealdb::Error = surrealdb::error::Api::Query(format!(
+            "Database index `users_username_unique` already contains '{}admin', with record `users:abc`",
+            GUARD_PREFIX
+        ))
+        .into();
+        match Error::from(err) {
+            Error::UniqueViolation { field } => assert_eq!(field, "username"),
+            err => panic!("unexpected error: {}", err),
+        }
+        let err: surrealdb::Error = surrealdb::error::Api::Query(format!(
+            "Found '{}admin' for field `role`, but expected a number",
+            GUARD_PREFIX
+        ))
+        .into();
+        assert!(matches!(Error::from(err), Error::Validation(_)));
+    }
+}
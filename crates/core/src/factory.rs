@@ -0,0 +1,105 @@
+use std::marker::PhantomData;
+
+use crate::hooks::ModelHooks;
+use crate::{Error, Model, Result, SurrealClient};
+
+type Override<I> = Box<dyn Fn(&mut I, u64) + Send + Sync>;
+
+/// Builds valid inputs of a model for tests, demos and seed data
+///
+/// Derived with `#[derive(Factory)]` next to `Model`, every input field is
+/// `Default::default()` unless set with `#[factory(default = <expr>)]` or
+/// `#[factory(sequence = "user{n}")]`, where `n` is the sequence number of
+/// the built input.
+///
+/// ```ignore
+/// #[derive(Model, Factory, Serialize, Deserialize)]
+/// struct User {
+///     #[field(primary)]
+///     id: Id<User>,
+///     #[factory(sequence = "user{n}")]
+///     username: String,
+///     #[factory(sequence = "user{n}@example.com")]
+///     email: String,
+/// }
+///
+/// let admin = User::factory()
+///     .with(|user| user.username = "admin".to_string())
+///     .create(&db)
+///     .await?;
+/// let users = User::factory().create_many(&db, 10).await?;
+/// ```
+pub trait Factory: ModelHooks {
+    /// Input of the `n`th record, before overrides are applied
+    fn definition(n: u64) -> Self::Input;
+
+    /// Next number of the sequence shared by all factories of the model, starting at 1
+    fn next_sequence() -> u64;
+
+    fn factory() -> FactoryBuilder<Self> {
+        FactoryBuilder::new()
+    }
+}
+
+/// Inputs of `T` with overridden fields, see [`Factory`]
+pub struct FactoryBuilder<T: Model> {
+    overrides: Vec<Override<T::Input>>,
+    _model: PhantomData<fn() -> T>,
+}
+
+impl<T: Factory> FactoryBuilder<T> {
+    pub fn new() -> Self {
+        FactoryBuilder {
+            overrides: vec![],
+            _model: PhantomData,
+        }
+    }
+
+    /// Change the built input, overrides run in the order they were added
+    pub fn with(mut self, f: impl Fn(&mut T::Input) + Send + Sync + 'static) -> Self {
+        self.overrides.push(Box::new(move |input, _| f(input)));
+        self
+    }
+
+    /// Change the built input depending on its sequence number
+    pub fn sequence(mut self, f: impl Fn(&mut T::Input, u64) + Send + Sync + 'static) -> Self {
+        self.overrides.push(Box::new(f));
+        self
+    }
+
+    pub fn build(&self) -> T::Input {
+        let n = T::next_sequence();
+        let mut input = T::definition(n);
+        for f in &self.overrides {
+            f(&mut input, n);
+        }
+        input
+    }
+
+    pub fn build_many(&self, count: usize) -> Vec<T::Input> {
+        (0..count).map(|_| self.build()).collect()
+    }
+
+    /// Build an input and create its record, running hooks and validation
+    pub async fn create(&self, client: &SurrealClient) -> Result<T> {
+        T::objects(client)
+            .create(self.build())
+            .await?
+            .ok_or(Error::NotFound)
+    }
+
+    /// Create `count` records one by one, see [`FactoryBuilder::create`]
+    pub async fn create_many(&self, client: &SurrealClient, count: usize) -> Result<Vec<T>> {
+        let mut created = Vec::with_capacity(count);
+        for _ in 0..count {
+            created.push(self.create(client).await?);
+        }
+        Ok(created)
+    }
+}
+
+impl<T: Factory> Default for FactoryBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,103 @@
+use std::marker::PhantomData;
+
+use serde::Deserialize;
+
+use crate::field::Link;
+use crate::query::Params;
+use crate::{Model, Query, Result};
+
+/// A record loaded together with the record its foreign key links to
+#[derive(Debug, Clone, Deserialize)]
+#[serde(bound(deserialize = "T: Model, R: Model"))]
+pub struct WithRelated<T, R> {
+    pub model: T,
+    /// `None` when the foreign key points to a missing record
+    pub related: Option<R>,
+}
+
+/// A [`Query`] eagerly loading the records linked by a foreign key
+pub struct Fetch<'c, T, R> {
+    query: Query<'c, T>,
+    field: &'static str,
+    _related: PhantomData<R>,
+}
+
+impl<'c, T, R> Fetch<'c, T, R>
+where
+    T: Model,
+    R: Model,
+{
+    pub(crate) fn new(query: Query<'c, T>, link: Link<T, R>) -> Self {
+        Fetch {
+            query,
+            field: link.name(),
+            _related: PhantomData,
+        }
+    }
+
+    pub(crate) fn render(&self, params: &mut Params) -> String {
+        // Ordering and pagination happen in the subquery, FETCH only
+        // resolves the links of the selected records
+        format!(
+            "SELECT $this AS model, {} AS related FROM ({}) FETCH related",
+            self.field,
+            self.query.render_select(params)
+        )
+    }
+
+    pub async fn all(&self) -> Result<Vec<WithRelated<T, R>>> {
+        let mut params = Params::default();
+        let sql = self.render(&mut params);
+        Ok(self
+            .query
+            .client
+            .query(sql)
+            .bind(params.into_bindings()?)
+            .await?
+            .take(0)?)
+    }
+
+    pub async fn first(mut self) -> Result<Option<WithRelated<T, R>>> {
+        self.query = self.query.limit(1);
+        Ok(self.all().await?.into_iter().next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+    use crate::{Condition, Order};
+
+    #[derive(Serialize, Deserialize)]
+    struct AnyModel {}
+
+    impl Model for AnyModel {
+        const TABLE_NAME: &'static str = "any_model";
+        #[cfg(feature = "utoipa")]
+        type Data = ();
+        type Input = ();
+        type Patch = ();
+        fn table_name(&self) -> &'static str {
+            Self::TABLE_NAME
+        }
+        #[cfg(feature = "utoipa")]
+        fn into_data(self) -> Self::Data {}
+    }
+
+    #[test]
+    fn test_render_fetch() {
+        let client = surrealdb::Surreal::init();
+        let fetch = Query::<AnyModel>::new(&client)
+            .filter(Condition::eq("name", "merak"))
+            .order_by("name", Order::Asc)
+            .limit(10)
+            .fetch(Link::<AnyModel, AnyModel>::new("owner"));
+        let mut params = Params::default();
+        assert_eq!(
+            fetch.render(&mut params),
+            "SELECT $this AS model, owner AS related FROM (SELECT * FROM type::table($table) WHERE name = $p0 ORDER BY name ASC LIMIT $limit) FETCH related"
+        );
+    }
+}
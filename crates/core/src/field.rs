@@ -0,0 +1,173 @@
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use serde::Serialize;
+
+use crate::id::Id;
+use crate::query::{Condition, Operator};
+
+/// A typed column of model `M` holding values of type `T`
+///
+/// Generated by the `Model` derive for every field, e.g. `User::fields.email`,
+/// so that conditions are checked against the Rust type of the column.
+/// `fields` is a constant rather than a module, `User::fields::email` can't
+/// be expressed on stable Rust.
+pub struct Field<M, T> {
+    name: &'static str,
+    cast: Option<&'static str>,
+    _marker: PhantomData<fn() -> (M, T)>,
+}
+
+impl<M, T> Field<M, T> {
+    pub const fn new(name: &'static str) -> Self {
+        Field {
+            name,
+            cast: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Cast compared values to the SurrealQL type `kind`
+    ///
+    /// Used for types such as `chrono::DateTime` which serialize as strings
+    /// but are stored as native SurrealDB values.
+    pub const fn cast(mut self, kind: &'static str) -> Self {
+        self.cast = Some(kind);
+        self
+    }
+
+    /// Column name in the database
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub(crate) const fn cast_kind(&self) -> Option<&'static str> {
+        self.cast
+    }
+}
+
+impl<M, T> Field<M, T>
+where
+    T: Serialize + 'static,
+{
+    fn compare<V: Serialize + 'static>(&self, op: Operator, value: V) -> Condition {
+        match self.cast {
+            Some(kind) => Condition::compare_cast(self.name, op, value, kind),
+            None => Condition::compare(self.name, op, value),
+        }
+    }
+
+    pub fn eq(&self, value: impl Into<T>) -> Condition {
+        self.compare(Operator::Eq, value.into())
+    }
+
+    pub fn ne(&self, value: impl Into<T>) -> Condition {
+        self.compare(Operator::Ne, value.into())
+    }
+
+    pub fn gt(&self, value: impl Into<T>) -> Condition {
+        self.compare(Operator::Gt, value.into())
+    }
+
+    pub fn gte(&self, value: impl Into<T>) -> Condition {
+        self.compare(Operator::Gte, value.into())
+    }
+
+    pub fn lt(&self, value: impl Into<T>) -> Condition {
+        self.compare(Operator::Lt, value.into())
+    }
+
+    pub fn lte(&self, value: impl Into<T>) -> Condition {
+        self.compare(Operator::Lte, value.into())
+    }
+
+    pub fn inside(&self, values: Vec<T>) -> Condition {
+        match self.cast {
+            Some(kind) => Condition::compare_cast(
+                self.name,
+                Operator::Inside,
+                values,
+                format!("array<{}>", kind),
+            ),
+            None => Condition::compare(self.name, Operator::Inside, values),
+        }
+    }
+
+    /// Match collections containing `value`, the element type is not checked
+    pub fn contains<V: Serialize + 'static>(&self, value: V) -> Condition {
+        Condition::compare(self.name, Operator::Contains, value)
+    }
+}
+
+impl<M, T> Clone for Field<M, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M, T> Copy for Field<M, T> {}
+
+impl<M, T> std::fmt::Debug for Field<M, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Field").field(&self.name).finish()
+    }
+}
+
+impl<M, T> From<Field<M, T>> for String {
+    fn from(field: Field<M, T>) -> Self {
+        field.name.to_string()
+    }
+}
+
+/// A foreign key column of model `M` linking to records of model `R`
+///
+/// Generated by the `Model` derive for `#[field(foreign_key = R)]` fields.
+/// Dereferences to a [`Field`] of [`Id<R>`] so it can be used in conditions,
+/// and carries the linked model for [`Query::fetch`](crate::Query::fetch).
+pub struct Link<M, R> {
+    field: Field<M, Id<R>>,
+    _marker: PhantomData<fn() -> R>,
+}
+
+impl<M, R> Link<M, R> {
+    pub const fn new(name: &'static str) -> Self {
+        Link {
+            field: Field::new(name),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<M, R> Deref for Link<M, R> {
+    type Target = Field<M, Id<R>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.field
+    }
+}
+
+impl<M, R> Clone for Link<M, R> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M, R> Copy for Link<M, R> {}
+
+impl<M, R> std::fmt::Debug for Link<M, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Link").field(&self.field.name()).finish()
+    }
+}
+
+impl<M, R> From<Link<M, R>> for Field<M, Id<R>> {
+    fn from(link: Link<M, R>) -> Self {
+        link.field
+    }
+}
+
+impl<M, R> From<Link<M, R>> for String {
+    fn from(link: Link<M, R>) -> Self {
+        link.field.name().to_string()
+    }
+}
@@ -0,0 +1,73 @@
+use std::future::Future;
+
+use surrealdb::RecordId;
+
+use crate::{Model, Result, SurrealClient};
+
+/// Logic run by [`Objects`](crate::Objects) around writes of a model
+///
+/// The `Model` derive implements this trait with no-op hooks, annotate the
+/// struct with `#[model(hooks)]` to provide your own implementation instead.
+/// An error returned by a `before_*` hook aborts the write, `after_*` hooks
+/// receive the written record.
+///
+/// ```ignore
+/// #[derive(Model, Serialize, Deserialize)]
+/// #[model(hooks)]
+/// struct Project { /* ... */ }
+///
+/// impl ModelHooks for Project {
+///     async fn after_delete(&self, client: &SurrealClient) -> merak_core::Result<()> {
+///         AuditLog::objects(client).create(AuditLog::deleted(&self.id)).await?;
+///         Ok(())
+///     }
+/// }
+/// ```
+///
+/// Hooks are not run for bulk writes like
+/// [`insert_many`](crate::Objects::insert_many) or through
+/// [`Query`](crate::Query). Writes queued in a
+/// [`Transaction`](crate::Transaction) cannot run them, so they are only
+/// available for models without hooks, see [`NoHooks`].
+pub trait ModelHooks: Model {
+    fn before_create(
+        _client: &SurrealClient,
+        _input: &mut Self::Input,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    fn after_create(&self, _client: &SurrealClient) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    fn before_update(
+        _client: &SurrealClient,
+        _id: &RecordId,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    fn after_update(&self, _client: &SurrealClient) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    fn before_delete(
+        _client: &SurrealClient,
+        _id: &RecordId,
+    ) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+
+    fn after_delete(&self, _client: &SurrealClient) -> impl Future<Output = Result<()>> + Send {
+        async { Ok(()) }
+    }
+}
+
+/// Models whose [`ModelHooks`] are the no-op defaults
+///
+/// Implemented by the `Model` derive unless the struct is annotated with
+/// `#[model(hooks)]`. Required by the writes of a
+/// [`Transaction`](crate::Transaction), which would otherwise skip the hooks
+/// that [`Objects`](crate::Objects) runs for the same model.
+pub trait NoHooks: ModelHooks {}
@@ -0,0 +1,256 @@
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use surrealdb::{RecordId, RecordIdKey};
+
+use crate::{Error, Model, Result};
+
+/// Id of a record of model `T`
+///
+/// Stored as a record link like a [`RecordId`], but the table is checked
+/// when parsing and deserializing so that an id of one model cannot be used
+/// for another. Displays as the bare key, which is what APIs expose.
+///
+/// ```ignore
+/// #[derive(Model, Serialize, Deserialize)]
+/// struct AuthSession {
+///     #[field(primary)]
+///     id: Id<AuthSession>,
+///     #[field(foreign_key = User)]
+///     user_id: Id<User>,
+/// }
+///
+/// let user_id: Id<User> = claims.sub.parse()?;
+/// let user = User::objects(db).get_by_id(&user_id).await?;
+/// ```
+pub struct Id<T> {
+    record: RecordId,
+    _model: PhantomData<fn() -> T>,
+}
+
+impl<T: Model> Id<T> {
+    pub fn new(key: impl Into<RecordIdKey>) -> Self {
+        Id {
+            record: RecordId::from((T::TABLE_NAME, key.into())),
+            _model: PhantomData,
+        }
+    }
+}
+
+impl<T> Id<T> {
+    pub fn key(&self) -> &RecordIdKey {
+        self.record.key()
+    }
+
+    pub fn record_id(&self) -> &RecordId {
+        &self.record
+    }
+
+    pub fn into_record_id(self) -> RecordId {
+        self.record
+    }
+}
+
+impl<T: Model> TryFrom<RecordId> for Id<T> {
+    type Error = Error;
+
+    fn try_from(record: RecordId) -> Result<Self> {
+        if record.table() != T::TABLE_NAME {
+            return Err(Error::Validation(format!(
+                "Expected a record of `{}`, found `{}`",
+                T::TABLE_NAME,
+                record
+            )));
+        }
+        Ok(Id {
+            record,
+            _model: PhantomData,
+        })
+    }
+}
+
+impl<T> From<Id<T>> for RecordId {
+    fn from(id: Id<T>) -> Self {
+        id.record
+    }
+}
+
+impl<T> From<&Id<T>> for RecordId {
+    fn from(id: &Id<T>) -> Self {
+        id.record.clone()
+    }
+}
+
+/// Parse `table:key`, checking the table, or a bare key as displayed, which
+/// is read as a number key if it is an integer and as a string key otherwise
+impl<T: Model> FromStr for Id<T> {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self> {
+        if value.is_empty() {
+            return Err(Error::Validation(format!(
+                "Invalid `{}` id: empty key",
+                T::TABLE_NAME
+            )));
+        }
+        if let Ok(key) = value.parse::<i64>() {
+            return Ok(Id::new(key));
+        }
+        match value.parse::<RecordId>() {
+            Ok(record) => record.try_into(),
+            Err(_) => Ok(Id::new(value)),
+        }
+    }
+}
+
+impl<T> fmt::Display for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // `RecordIdKey` displays escaped keys like `a-b`, show them as is
+        let key = self.key().clone();
+        if let Ok(key) = String::try_from(key.clone()) {
+            return write!(f, "{}", key);
+        }
+        if let Ok(key) = i64::try_from(key) {
+            return write!(f, "{}", key);
+        }
+        write!(f, "{}", self.key())
+    }
+}
+
+impl<T> fmt::Debug for Id<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Id").field(&self.record).finish()
+    }
+}
+
+impl<T> Clone for Id<T> {
+    fn clone(&self) -> Self {
+        Id {
+            record: self.record.clone(),
+            _model: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for Id<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.record == other.record
+    }
+}
+
+impl<T> Eq for Id<T> {}
+
+impl<T> Hash for Id<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.record.hash(state);
+    }
+}
+
+impl<T> Serialize for Id<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.record.serialize(serializer)
+    }
+}
+
+impl<'de, T: Model> Deserialize<'de> for Id<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let record = RecordId::deserialize(deserializer)?;
+        Id::try_from(record).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Values identifying a record of `T`, accepted by [`Objects`](crate::Objects)
+///
+/// Implemented for [`Id<T>`] and for record keys as strings.
+pub trait IntoRecordId<T> {
+    fn into_record_id(self) -> RecordId;
+}
+
+impl<T> IntoRecordId<T> for Id<T> {
+    fn into_record_id(self) -> RecordId {
+        self.record
+    }
+}
+
+impl<T> IntoRecordId<T> for &Id<T> {
+    fn into_record_id(self) -> RecordId {
+        self.record.clone()
+    }
+}
+
+impl<T: Model> IntoRecordId<T> for &str {
+    fn into_record_id(self) -> RecordId {
+        RecordId::from((T::TABLE_NAME, self))
+    }
+}
+
+impl<T: Model> IntoRecordId<T> for String {
+    fn into_record_id(self) -> RecordId {
+        RecordId::from((T::TABLE_NAME, self))
+    }
+}
+
+impl<T: Model> IntoRecordId<T> for &String {
+    fn into_record_id(self) -> RecordId {
+        RecordId::from((T::TABLE_NAME, self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct User {}
+
+    impl Model for User {
+        const TABLE_NAME: &'static str = "users";
+        #[cfg(feature = "utoipa")]
+        type Data = ();
+        type Input = ();
+        type Patch = ();
+        fn table_name(&self) -> &'static str {
+            Self::TABLE_NAME
+        }
+        #[cfg(feature = "utoipa")]
+        fn into_data(self) -> Self::Data {}
+    }
+
+    #[test]
+    fn test_parse() {
+        let id: Id<User> = "users:⟨1b2c-3d⟩".parse().unwrap();
+        assert_eq!(id, Id::new("1b2c-3d"));
+        assert_eq!(id.to_string(), "1b2c-3d");
+        assert_eq!(id.to_string().parse::<Id<User>>().unwrap(), id);
+        assert_eq!(Id::<User>::new(42).to_string(), "42");
+        assert!(matches!(
+            "sessions:abc".parse::<Id<User>>(),
+            Err(Error::Validation(_))
+        ));
+        assert!("".parse::<Id<User>>().is_err());
+    }
+
+    #[test]
+    fn test_parse_number_key() {
+        let id = Id::<User>::new(1);
+        assert_eq!(id.to_string(), "1");
+        assert_eq!(id.to_string().parse::<Id<User>>().unwrap(), id);
+        assert_eq!("users:1".parse::<Id<User>>().unwrap(), id);
+        assert_eq!("-7".parse::<Id<User>>().unwrap(), Id::new(-7));
+        assert_eq!("1a".parse::<Id<User>>().unwrap(), Id::new("1a"));
+    }
+
+    #[test]
+    fn test_record_id() {
+        let id = Id::<User>::new("abc");
+        assert_eq!(RecordId::from(&id), RecordId::from(("users", "abc")));
+        assert_eq!(
+            IntoRecordId::<User>::into_record_id("abc"),
+            id.clone().into()
+        );
+        assert!(Id::<User>::try_from(RecordId::from(("sessions", "abc"))).is_err());
+    }
+}
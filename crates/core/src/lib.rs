@@ -1,11 +1,58 @@
+use std::pin::pin;
+
+use futures::{Stream, StreamExt, stream};
 use serde::Serialize;
-use surrealdb::Surreal;
 use surrealdb::engine::any::Any;
+use surrealdb::{RecordId, Surreal};
 
+pub mod aggregate;
+pub mod error;
+pub mod factory;
+pub mod fetch;
+pub mod field;
+pub mod hooks;
+pub mod id;
+pub mod live;
+pub mod migration;
+pub mod page;
 pub mod prelude;
+pub mod query;
+pub mod registry;
+pub mod relation;
+pub mod schema;
+pub mod seed;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod transaction;
+pub mod validation;
+
+pub use aggregate::{Group, GroupBy};
+pub use error::{Error, Result};
+pub use factory::{Factory, FactoryBuilder};
+pub use fetch::{Fetch, WithRelated};
+pub use field::{Field, Link};
+pub use hooks::{ModelHooks, NoHooks};
+pub use id::{Id, IntoRecordId};
+pub use live::{Action, LiveStream, Notification};
+pub use page::{Page, Paginate};
+pub use query::{Condition, Operator, Order, Query};
+pub use relation::Relation;
+pub use schema::FieldKind;
+pub use seed::{Fixtures, Seeded, Seeder};
+pub use transaction::Transaction;
+pub use validation::Validate;
+
+pub use surrealdb::opt::{PatchOp, PatchOps};
+
+/// The `surrealdb` version used by merak, referred to by the derived code so
+/// that crates deriving `Model` don't need a matching direct dependency
+pub use surrealdb;
 
 pub type SurrealClient = Surreal<Any>;
 
+/// Number of records sent per `INSERT` by [`Objects::insert_many`]
+pub const INSERT_CHUNK_SIZE: usize = 1000;
+
 pub trait Model
 where
     Self: Serialize + for<'de> serde::Deserialize<'de> + Sized,
@@ -13,13 +60,26 @@ where
     const TABLE_NAME: &'static str;
     #[cfg(feature = "utoipa")]
     type Data: Serialize + 'static;
-    type Input: Serialize + 'static;
+    type Input: Serialize + Validate + 'static;
+    /// Input with every field optional, used for partial updates
+    type Patch: Serialize + Validate + 'static;
+    /// Field compared and incremented by writes, set by `#[field(version)]`
+    const VERSION_FIELD: Option<&'static str> = None;
+    /// Whether deletes only set `deleted_at`, set by `#[model(soft_delete)]`
+    const SOFT_DELETE: bool = false;
     fn table_name(&self) -> &'static str;
     #[cfg(feature = "utoipa")]
     fn into_data(self) -> Self::Data;
     fn objects(client: &SurrealClient) -> Objects<'_, Self, Self::Input> {
         Objects::new(client)
     }
+    /// Id of the record, `None` for models without a primary key
+    fn record_id(&self) -> Option<surrealdb::RecordId> {
+        None
+    }
+    fn schema() -> schema::TableSchema {
+        schema::TableSchema::new(Self::TABLE_NAME)
+    }
 }
 
 pub struct Objects<'c, T, I> {
@@ -41,39 +101,358 @@ where
         }
     }
 
-    pub async fn create(&self, data: I) -> surrealdb::Result<Option<T>> {
-        self.client.create(T::TABLE_NAME).content(data).await
+    /// Start a query over all records of the table
+    pub fn query(&self) -> Query<'c, T> {
+        Query::new(self.client)
+    }
+
+    /// Start a query filtered by `condition`
+    pub fn filter(&self, condition: Condition) -> Query<'c, T> {
+        self.query().filter(condition)
+    }
+
+    /// Watch all records of the table, see [`Query::live`]
+    pub async fn live(&self) -> Result<LiveStream<T>>
+    where
+        T: Unpin + Send + 'static,
+    {
+        self.query().live().await
+    }
+
+    /// Start a query that also matches soft deleted records
+    pub fn with_deleted(&self) -> Query<'c, T> {
+        self.query().with_deleted()
+    }
+
+    /// Start a query over the soft deleted records only
+    pub fn only_deleted(&self) -> Query<'c, T> {
+        self.query().only_deleted()
+    }
+
+    /// Paginate all records, see [`Query::paginate`]
+    pub fn paginate(&self, limit: u64) -> Paginate<'c, T> {
+        self.query().paginate(limit)
+    }
+
+    /// Load all records together with the records linked by `link`
+    pub fn fetch<R: Model>(&self, link: Link<T, R>) -> Fetch<'c, T, R> {
+        self.query().fetch(link)
+    }
+
+    pub async fn create(&self, mut data: I) -> Result<Option<T>>
+    where
+        T: ModelHooks<Input = I>,
+        I: Validate,
+    {
+        T::before_create(self.client, &mut data).await?;
+        data.validate()?;
+        let created: Option<T> = self.client.create(T::TABLE_NAME).content(data).await?;
+        if let Some(created) = &created {
+            created.after_create(self.client).await?;
+        }
+        Ok(created)
+    }
+
+    pub async fn create_with_id(&self, id: String, mut data: I) -> Result<Option<T>>
+    where
+        T: ModelHooks<Input = I>,
+        I: Validate,
+    {
+        T::before_create(self.client, &mut data).await?;
+        data.validate()?;
+        let created: Option<T> = self
+            .client
+            .create((T::TABLE_NAME, id))
+            .content(data)
+            .await?;
+        if let Some(created) = &created {
+            created.after_create(self.client).await?;
+        }
+        Ok(created)
+    }
+
+    pub async fn create_many(&self, mut data: Vec<I>) -> Result<Option<Vec<T>>>
+    where
+        T: ModelHooks<Input = I>,
+        I: Validate,
+    {
+        for data in &mut data {
+            T::before_create(self.client, data).await?;
+            data.validate()?;
+        }
+        let created: Option<Vec<T>> = self.client.create(T::TABLE_NAME).content(data).await?;
+        for created in created.iter().flatten() {
+            created.after_create(self.client).await?;
+        }
+        Ok(created)
+    }
+
+    /// Insert records in chunks of [`INSERT_CHUNK_SIZE`], returning how many were inserted
+    ///
+    /// Inputs are validated but [`ModelHooks`] are not run. Chunks are
+    /// inserted one after the other, those inserted before a failing chunk
+    /// are kept.
+    pub async fn insert_many(&self, data: impl IntoIterator<Item = I>) -> Result<u64>
+    where
+        I: Validate,
+    {
+        self.insert_stream(stream::iter(data)).await
+    }
+
+    /// Insert records read from `data`, see [`insert_many`](Objects::insert_many)
+    ///
+    /// Only one chunk is held in memory at a time.
+    ///
+    /// ```ignore
+    /// let tasks = csv_rows.map(|row| TaskInput::from(row));
+    /// let inserted = Task::objects(db).insert_stream(tasks).await?;
+    /// ```
+    pub async fn insert_stream(&self, data: impl Stream<Item = I>) -> Result<u64>
+    where
+        I: Validate,
+    {
+        let mut chunks = pin!(data.chunks(INSERT_CHUNK_SIZE));
+        let mut inserted = 0;
+        while let Some(chunk) = chunks.next().await {
+            for data in &chunk {
+                data.validate()?;
+            }
+            let sql = format!("array::len(({}))", query::render_insert::<T>());
+            let count: Option<u64> = self
+                .client
+                .query(sql)
+                .bind(("data", chunk))
+                .await?
+                .take(0)?;
+            inserted += count.unwrap_or_default();
+        }
+        Ok(inserted)
     }
 
-    pub async fn create_with_id(&self, id: String, data: I) -> surrealdb::Result<Option<T>> {
-        self.client.create((T::TABLE_NAME, id)).content(data).await
+    pub async fn get_by_id(&self, id: impl IntoRecordId<T>) -> Result<Option<T>> {
+        let id = id.into_record_id();
+        if T::SOFT_DELETE {
+            let sql = format!("SELECT * FROM $id WHERE {} = NONE", schema::DELETED_AT);
+            let mut response = self.client.query(sql).bind(("id", id)).await?;
+            return Ok(response.take(0)?);
+        }
+        Ok(self.client.select(id).await?)
     }
 
-    pub async fn create_many(&self, data: Vec<I>) -> surrealdb::Result<Option<Vec<T>>> {
-        self.client.create(T::TABLE_NAME).content(data).await
+    /// Replace the record with `data`
+    ///
+    /// For versioned models the write only happens if the version in `data`
+    /// matches the stored one, otherwise [`Error::Conflict`] is returned.
+    /// Without a version in `data` the record is replaced unconditionally.
+    pub async fn update(&self, id: impl IntoRecordId<T>, data: I) -> Result<Option<T>>
+    where
+        T: ModelHooks,
+        I: Validate,
+    {
+        data.validate()?;
+        let id = id.into_record_id();
+        T::before_update(self.client, &id).await?;
+        let updated = match T::VERSION_FIELD {
+            Some(field) => self.write_versioned(id, "CONTENT", data, field).await?,
+            None => self.client.update(id).content(data).await?,
+        };
+        self.after_update(updated).await
     }
 
-    pub async fn get_by_id(&self, id: &str) -> surrealdb::Result<Option<T>> {
-        self.client.select((T::TABLE_NAME, id)).await
+    /// Replace the record `id` with `model`, as done by the derived `save`
+    ///
+    /// Versioned like [`update`](Objects::update).
+    pub async fn save(&self, id: impl Into<RecordId>, model: T) -> Result<Option<T>>
+    where
+        T: ModelHooks + Validate + 'static,
+    {
+        model.validate()?;
+        let id = id.into();
+        T::before_update(self.client, &id).await?;
+        let updated = match T::VERSION_FIELD {
+            Some(field) => self.write_versioned(id, "CONTENT", model, field).await?,
+            None => self.client.update(id).content(model).await?,
+        };
+        self.after_update(updated).await
     }
 
-    pub async fn update(&self, id: &str, data: I) -> surrealdb::Result<Option<T>> {
-        self.client.update((T::TABLE_NAME, id)).content(data).await
+    /// Update only the fields set in `patch`
+    ///
+    /// For versioned models the write is conditional when `patch` sets the version.
+    pub async fn merge(&self, id: impl IntoRecordId<T>, patch: T::Patch) -> Result<Option<T>>
+    where
+        T: ModelHooks,
+    {
+        self.merge_record(id.into_record_id(), patch).await
     }
 
-    pub async fn upsert(&self, id: &str, data: I) -> surrealdb::Result<Option<T>> {
-        self.client.upsert((T::TABLE_NAME, id)).content(data).await
+    /// Update only the fields set in `patch` on the record `id`, as done by the derived `merge`
+    pub async fn merge_record(&self, id: impl Into<RecordId>, patch: T::Patch) -> Result<Option<T>>
+    where
+        T: ModelHooks,
+    {
+        patch.validate()?;
+        let id = id.into();
+        T::before_update(self.client, &id).await?;
+        let updated = match T::VERSION_FIELD {
+            Some(field) => self.write_versioned(id, "MERGE", patch, field).await?,
+            None => self.client.update(id).merge(patch).await?,
+        };
+        self.after_update(updated).await
     }
 
-    pub async fn delete(&self, id: &str) -> surrealdb::Result<Option<T>> {
-        self.client.delete((T::TABLE_NAME, id)).await
+    /// Update the record only if `field` is unset in `data` or equal to the stored value
+    async fn write_versioned<D>(
+        &self,
+        id: RecordId,
+        operation: &str,
+        data: D,
+        field: &str,
+    ) -> Result<Option<T>>
+    where
+        D: Serialize + 'static,
+    {
+        let field = query::escape_field(field);
+        let sql = format!(
+            "UPDATE $id {op} $data WHERE $data.{f} = NONE OR {f} = $data.{f};\nSELECT VALUE id FROM $id",
+            op = operation,
+            f = field
+        );
+        let mut response = self
+            .client
+            .query(sql)
+            .bind(("id", id))
+            .bind(("data", data))
+            .await?;
+        let updated: Option<T> = response.take(0)?;
+        let existing: Vec<RecordId> = response.take(1)?;
+        match updated {
+            None if !existing.is_empty() => {
+                Err(Error::Conflict(error::VERSION_CONFLICT.to_string()))
+            }
+            updated => Ok(updated),
+        }
     }
 
-    pub async fn all(&self) -> surrealdb::Result<Vec<T>> {
-        self.client.select(T::TABLE_NAME).await
+    async fn after_update(&self, updated: Option<T>) -> Result<Option<T>>
+    where
+        T: ModelHooks,
+    {
+        if let Some(updated) = &updated {
+            updated.after_update(self.client).await?;
+        }
+        Ok(updated)
+    }
+
+    /// Apply JSON Patch operations to the record
+    ///
+    /// The version of versioned models is incremented but not checked.
+    ///
+    /// ```ignore
+    /// User::objects(db)
+    ///     .patch(id, PatchOps::new().replace("/email", email).remove("/nickname"))
+    ///     .await?;
+    /// ```
+    pub async fn patch(
+        &self,
+        id: impl IntoRecordId<T>,
+        ops: impl Into<PatchOp>,
+    ) -> Result<Option<T>>
+    where
+        T: ModelHooks,
+    {
+        let id = id.into_record_id();
+        T::before_update(self.client, &id).await?;
+        let updated = self.client.update(id).patch(ops).await?;
+        self.after_update(updated).await
+    }
+
+    /// Create or replace the record, without running [`ModelHooks`]
+    pub async fn upsert(&self, id: impl IntoRecordId<T>, data: I) -> Result<Option<T>> {
+        Ok(self
+            .client
+            .upsert(id.into_record_id())
+            .content(data)
+            .await?)
+    }
+
+    /// Update the fields set in `patch` on the records matching `condition`, see [`Query::update`]
+    pub async fn update_where(&self, condition: Condition, patch: T::Patch) -> Result<u64> {
+        self.filter(condition).update(patch).await
+    }
+
+    /// Delete the records matching `condition`, returning how many were deleted
+    ///
+    /// Soft deleting models only mark them as deleted.
+    pub async fn delete_where(&self, condition: Condition) -> Result<u64> {
+        self.filter(condition).delete_count().await
+    }
+
+    /// Delete the record, soft deleting models only mark it as deleted
+    pub async fn delete(&self, id: impl IntoRecordId<T>) -> Result<Option<T>>
+    where
+        T: ModelHooks,
+    {
+        self.delete_record(id.into_record_id()).await
+    }
+
+    /// Delete the record `id`, as done by the derived `delete`
+    pub async fn delete_record(&self, id: impl Into<RecordId>) -> Result<Option<T>>
+    where
+        T: ModelHooks,
+    {
+        let id = id.into();
+        T::before_delete(self.client, &id).await?;
+        let deleted: Option<T> = if T::SOFT_DELETE {
+            let sql = query::render_delete_record::<T>("$id");
+            self.client.query(sql).bind(("id", id)).await?.take(0)?
+        } else {
+            self.client.delete(id).await?
+        };
+        if let Some(deleted) = &deleted {
+            deleted.after_delete(self.client).await?;
+        }
+        Ok(deleted)
+    }
+
+    /// Permanently delete the record, even for soft deleting models
+    pub async fn force_delete(&self, id: impl IntoRecordId<T>) -> Result<Option<T>>
+    where
+        T: ModelHooks,
+    {
+        let id = id.into_record_id();
+        T::before_delete(self.client, &id).await?;
+        let deleted: Option<T> = self.client.delete(id).await?;
+        if let Some(deleted) = &deleted {
+            deleted.after_delete(self.client).await?;
+        }
+        Ok(deleted)
+    }
+
+    /// Undo the soft delete of the record
+    ///
+    /// Returns `None` if the record does not exist or is not deleted.
+    pub async fn restore(&self, id: impl IntoRecordId<T>) -> Result<Option<T>> {
+        let sql = format!(
+            "UPDATE $id SET {field} = NONE WHERE {field} != NONE",
+            field = schema::DELETED_AT
+        );
+        let mut response = self
+            .client
+            .query(sql)
+            .bind(("id", id.into_record_id()))
+            .await?;
+        Ok(response.take(0)?)
+    }
+
+    pub async fn all(&self) -> Result<Vec<T>> {
+        if T::SOFT_DELETE {
+            return self.query().all().await;
+        }
+        Ok(self.client.select(T::TABLE_NAME).await?)
     }
 
-    pub async fn drop(&self) -> surrealdb::Result<Vec<T>> {
-        self.client.delete(T::TABLE_NAME).await
+    pub async fn drop(&self) -> Result<Vec<T>> {
+        Ok(self.client.delete(T::TABLE_NAME).await?)
     }
 }
@@ -0,0 +1,39 @@
+use futures::stream::BoxStream;
+
+/// Change that triggered a [`Notification`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+}
+
+/// A change to a record matched by a live query
+///
+/// For deletions `data` holds the record as it was before it was deleted.
+#[derive(Debug, Clone)]
+pub struct Notification<T> {
+    pub action: Action,
+    pub data: T,
+}
+
+impl<T> Notification<T> {
+    /// Convert a SurrealDB notification, `None` for actions this crate does not know
+    pub(crate) fn from_surreal(notification: surrealdb::Notification<T>) -> Option<Self> {
+        let action = match notification.action {
+            surrealdb::Action::Create => Action::Create,
+            surrealdb::Action::Update => Action::Update,
+            surrealdb::Action::Delete => Action::Delete,
+            _ => return None,
+        };
+        Some(Notification {
+            action,
+            data: notification.data,
+        })
+    }
+}
+
+/// Stream of changes returned by [`Query::live`](crate::Query::live)
+///
+/// The live query is killed when the stream is dropped.
+pub type LiveStream<T> = BoxStream<'static, crate::Result<Notification<T>>>;
@@ -0,0 +1,749 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{error::Error as StdError, fmt};
+
+use serde::Deserialize;
+
+use crate::SurrealClient;
+use crate::query::escape_field;
+use crate::schema::{Schema, TableSchema};
+
+/// Table recording applied migrations
+pub const MIGRATIONS_TABLE: &str = "_migrations";
+/// Table holding the lock taken while migrations run
+pub const LOCK_TABLE: &str = "_migrations_lock";
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+type MigrationFn =
+    Box<dyn for<'a> Fn(&'a SurrealClient) -> BoxFuture<'a, surrealdb::Result<()>> + Send + Sync>;
+
+#[derive(Debug)]
+pub enum MigrationError {
+    /// Two migrations share the same version
+    DuplicateVersion(u64),
+    /// Another instance holds the migration lock
+    Locked(String),
+    /// The migration has no down step and cannot be reverted
+    Irreversible(u64),
+    /// A migration recorded in the database is not known to the migrator
+    Unknown(u64),
+    Database(surrealdb::Error),
+}
+
+pub type MigrationResult<T> = std::result::Result<T, MigrationError>;
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::DuplicateVersion(version) => {
+                write!(f, "Duplicate migration version {}", version)
+            }
+            MigrationError::Locked(owner) => write!(f, "Migrations are locked by {}", owner),
+            MigrationError::Irreversible(version) => {
+                write!(f, "Migration {} cannot be reverted", version)
+            }
+            MigrationError::Unknown(version) => {
+                write!(f, "Applied migration {} is unknown", version)
+            }
+            MigrationError::Database(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl StdError for MigrationError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            MigrationError::Database(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<surrealdb::Error> for MigrationError {
+    fn from(err: surrealdb::Error) -> Self {
+        MigrationError::Database(err)
+    }
+}
+
+/// A single up or down step of a migration
+pub enum Step {
+    /// SurrealQL statements, typically embedded with `include_str!`
+    Sql(&'static str),
+    /// Rust code receiving the database client
+    Func(MigrationFn),
+}
+
+impl fmt::Debug for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Sql(sql) => f.debug_tuple("Sql").field(sql).finish(),
+            Step::Func(_) => f.debug_tuple("Func").finish(),
+        }
+    }
+}
+
+/// A versioned schema or data change
+///
+/// ```ignore
+/// Migration::sql(1, "create_projects", include_str!("../migrations/0001_create_projects.surql"))
+///     .down_sql("REMOVE TABLE projects");
+///
+/// Migration::func(2, "backfill_slugs", |db| Box::pin(async move {
+///     db.query("UPDATE projects SET slug = string::slug(name)").await?.check()?;
+///     Ok(())
+/// }));
+/// ```
+#[derive(Debug)]
+pub struct Migration {
+    pub version: u64,
+    pub name: &'static str,
+    up: Step,
+    down: Option<Step>,
+}
+
+impl Migration {
+    pub fn sql(version: u64, name: &'static str, up: &'static str) -> Self {
+        Migration {
+            version,
+            name,
+            up: Step::Sql(up),
+            down: None,
+        }
+    }
+
+    pub fn func<F>(version: u64, name: &'static str, up: F) -> Self
+    where
+        F: for<'a> Fn(&'a SurrealClient) -> BoxFuture<'a, surrealdb::Result<()>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        Migration {
+            version,
+            name,
+            up: Step::Func(Box::new(up)),
+            down: None,
+        }
+    }
+
+    pub fn down_sql(mut self, down: &'static str) -> Self {
+        self.down = Some(Step::Sql(down));
+        self
+    }
+
+    pub fn down_func<F>(mut self, down: F) -> Self
+    where
+        F: for<'a> Fn(&'a SurrealClient) -> BoxFuture<'a, surrealdb::Result<()>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.down = Some(Step::Func(Box::new(down)));
+        self
+    }
+
+    pub fn is_reversible(&self) -> bool {
+        self.down.is_some()
+    }
+}
+
+/// A migration recorded in the `_migrations` table
+#[derive(Debug, Clone, Deserialize)]
+pub struct AppliedMigration {
+    pub version: u64,
+    pub name: String,
+    pub applied_at: surrealdb::Datetime,
+}
+
+/// Difference between the derived model schemas and the live database
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Drift {
+    MissingTable(String),
+    UnexpectedTable(String),
+    TableMismatch {
+        table: String,
+        expected: String,
+        actual: String,
+    },
+    MissingField {
+        table: String,
+        field: String,
+    },
+    UnexpectedField {
+        table: String,
+        field: String,
+    },
+    FieldMismatch {
+        table: String,
+        field: String,
+        expected: String,
+        actual: String,
+    },
+    MissingIndex {
+        table: String,
+        index: String,
+    },
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Drift::MissingTable(table) => write!(f, "Table `{}` is not defined", table),
+            Drift::UnexpectedTable(table) => write!(f, "Table `{}` has no model", table),
+            Drift::TableMismatch {
+                table,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Table `{}` expected `{}` but found `{}`",
+                table, expected, actual
+            ),
+            Drift::MissingField { table, field } => {
+                write!(f, "Field `{}.{}` is not defined", table, field)
+            }
+            Drift::UnexpectedField { table, field } => {
+                write!(f, "Field `{}.{}` has no model field", table, field)
+            }
+            Drift::FieldMismatch {
+                table,
+                field,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "Field `{}.{}` expected `{}` but found `{}`",
+                table, field, expected, actual
+            ),
+            Drift::MissingIndex { table, index } => {
+                write!(f, "Index `{}` on `{}` is not defined", index, table)
+            }
+        }
+    }
+}
+
+/// Table definitions as reported by `INFO FOR DB` and `INFO FOR TABLE`
+#[derive(Debug, Clone, Default)]
+pub struct DbInfo {
+    pub tables: BTreeMap<String, TableInfo>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TableInfo {
+    #[serde(skip)]
+    pub definition: String,
+    #[serde(default)]
+    pub fields: BTreeMap<String, String>,
+    #[serde(default)]
+    pub indexes: BTreeMap<String, String>,
+}
+
+impl DbInfo {
+    /// Load the definitions of every table in the current database
+    pub async fn load(client: &SurrealClient) -> surrealdb::Result<Self> {
+        #[derive(Deserialize)]
+        struct Tables {
+            #[serde(default)]
+            tables: BTreeMap<String, String>,
+        }
+
+        let tables: Option<Tables> = client.query("INFO FOR DB").await?.take(0)?;
+        let mut info = DbInfo::default();
+        for (table, definition) in tables.map(|tables| tables.tables).unwrap_or_default() {
+            let sql = format!("INFO FOR TABLE {}", escape_field(&table));
+            let table_info: Option<TableInfo> = client.query(sql).await?.take(0)?;
+            let mut table_info = table_info.unwrap_or_default();
+            table_info.definition = definition;
+            info.tables.insert(table, table_info);
+        }
+        Ok(info)
+    }
+
+    /// Compare against the expected schema, internal migration tables are ignored
+    pub fn diff(&self, schema: &Schema) -> Vec<Drift> {
+        let mut drifts = vec![];
+        for table in schema.tables() {
+            match self.tables.get(table.table) {
+                Some(info) => Self::diff_table(table, info, &mut drifts),
+                None => drifts.push(Drift::MissingTable(table.table.to_string())),
+            }
+        }
+        for table in self.tables.keys() {
+            let known = schema.tables().iter().any(|schema| schema.table == table);
+            if !known && table != MIGRATIONS_TABLE && table != LOCK_TABLE {
+                drifts.push(Drift::UnexpectedTable(table.clone()));
+            }
+        }
+        drifts
+    }
+
+    fn diff_table(table: &TableSchema, info: &TableInfo, drifts: &mut Vec<Drift>) {
+        let expected = table.definition();
+        if !info.definition.contains(&expected) {
+            drifts.push(Drift::TableMismatch {
+                table: table.table.to_string(),
+                expected,
+                actual: info.definition.clone(),
+            });
+        }
+        for field in &table.fields {
+            match info.fields.get(field.name) {
+                Some(actual) if !actual.contains(&field.definition()) => {
+                    drifts.push(Drift::FieldMismatch {
+                        table: table.table.to_string(),
+                        field: field.name.to_string(),
+                        expected: field.definition(),
+                        actual: actual.clone(),
+                    })
+                }
+                Some(_) => {}
+                None => drifts.push(Drift::MissingField {
+                    table: table.table.to_string(),
+                    field: field.name.to_string(),
+                }),
+            }
+        }
+        for field in info.fields.keys() {
+            let root = field.split(['.', '[']).next().unwrap_or(field);
+            // `in` and `out` are defined by SurrealDB for edge tables
+            if table.relation.is_some() && (root == "in" || root == "out") {
+                continue;
+            }
+            if !table.fields.iter().any(|schema| schema.name == root) {
+                drifts.push(Drift::UnexpectedField {
+                    table: table.table.to_string(),
+                    field: field.clone(),
+                });
+            }
+        }
+        for index in &table.indexes {
+            if !info.indexes.contains_key(&index.name) {
+                drifts.push(Drift::MissingIndex {
+                    table: table.table.to_string(),
+                    index: index.name.clone(),
+                });
+            }
+        }
+    }
+}
+
+/// Runs migrations in version order and records them in `_migrations`
+///
+/// A lock record in `_migrations_lock` prevents two instances from migrating
+/// concurrently. Locks older than the lock timeout are considered stale and
+/// are taken over.
+pub struct Migrator {
+    migrations: Vec<Migration>,
+    schema: Option<Schema>,
+    lock_timeout: Duration,
+}
+
+impl Migrator {
+    pub fn new() -> Self {
+        Migrator {
+            migrations: vec![],
+            schema: None,
+            lock_timeout: Duration::from_secs(600),
+        }
+    }
+
+    /// Define the tables of `schema` in [`up`](Migrator::up), under the lock
+    /// and before the pending migrations
+    pub fn schema(mut self, schema: Schema) -> Self {
+        self.schema = Some(schema);
+        self
+    }
+
+    pub fn register(mut self, migration: Migration) -> Self {
+        self.migrations.push(migration);
+        self
+    }
+
+    pub fn lock_timeout(mut self, timeout: Duration) -> Self {
+        self.lock_timeout = timeout;
+        self
+    }
+
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    #[allow(clippy::result_large_err)]
+    fn sorted(&self) -> MigrationResult<Vec<&Migration>> {
+        let mut migrations = self.migrations.iter().collect::<Vec<_>>();
+        migrations.sort_by_key(|migration| migration.version);
+        if let Some(pair) = migrations
+            .windows(2)
+            .find(|pair| pair[0].version == pair[1].version)
+        {
+            return Err(MigrationError::DuplicateVersion(pair[0].version));
+        }
+        Ok(migrations)
+    }
+
+    /// List migrations recorded in the database, oldest first
+    pub async fn applied(&self, client: &SurrealClient) -> MigrationResult<Vec<AppliedMigration>> {
+        let applied = client
+            .query("SELECT version, name, applied_at FROM type::table($table) ORDER BY version ASC")
+            .bind(("table", MIGRATIONS_TABLE))
+            .await?
+            .take(0)?;
+        Ok(applied)
+    }
+
+    /// List migrations which have not been applied yet
+    pub async fn pending(&self, client: &SurrealClient) -> MigrationResult<Vec<&Migration>> {
+        let applied = self.applied(client).await?;
+        Ok(self
+            .sorted()?
+            .into_iter()
+            .filter(|migration| {
+                !applied
+                    .iter()
+                    .any(|applied| applied.version == migration.version)
+            })
+            .collect())
+    }
+
+    /// Apply the schema and all pending migrations, returning their versions
+    pub async fn up(&self, client: &SurrealClient) -> MigrationResult<Vec<u64>> {
+        let owner = self.lock(client).await?;
+        let result = self.run_up(client).await;
+        self.unlock(client, &owner).await?;
+        result
+    }
+
+    async fn run_up(&self, client: &SurrealClient) -> MigrationResult<Vec<u64>> {
+        if let Some(schema) = &self.schema {
+            schema.apply(client).await?;
+        }
+        let mut versions = vec![];
+        for migration in self.pending(client).await? {
+            run_step(client, &migration.up, Some(migration)).await?;
+            versions.push(migration.version);
+        }
+        Ok(versions)
+    }
+
+    /// Revert the last `steps` applied migrations, returning their versions
+    pub async fn down(&self, client: &SurrealClient, steps: usize) -> MigrationResult<Vec<u64>> {
+        let owner = self.lock(client).await?;
+        let result = self.run_down(client, steps).await;
+        self.unlock(client, &owner).await?;
+        result
+    }
+
+    async fn run_down(&self, client: &SurrealClient, steps: usize) -> MigrationResult<Vec<u64>> {
+        let mut versions = vec![];
+        for applied in self.applied(client).await?.iter().rev().take(steps) {
+            let migration = self
+                .migrations
+                .iter()
+                .find(|migration| migration.version == applied.version)
+                .ok_or(MigrationError::Unknown(applied.version))?;
+            let down = migration
+                .down
+                .as_ref()
+                .ok_or(MigrationError::Irreversible(migration.version))?;
+            run_step(client, down, None).await?;
+            forget(client, migration.version).await?;
+            versions.push(migration.version);
+        }
+        Ok(versions)
+    }
+
+    /// Compare the derived model schemas against the live database
+    pub async fn drift(
+        &self,
+        client: &SurrealClient,
+        schema: &Schema,
+    ) -> MigrationResult<Vec<Drift>> {
+        Ok(DbInfo::load(client).await?.diff(schema))
+    }
+
+    async fn lock(&self, client: &SurrealClient) -> MigrationResult<String> {
+        #[derive(Deserialize)]
+        struct Lock {
+            owner: String,
+        }
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos())
+            .unwrap_or_default();
+        let owner = format!("{}-{}", std::process::id(), nanos);
+        client
+            .query("DELETE type::thing($table, 'lock') WHERE acquired_at < time::now() - <duration>$timeout")
+            .bind(("table", LOCK_TABLE))
+            .bind(("timeout", format!("{}s", self.lock_timeout.as_secs())))
+            .await?
+            .check()?;
+        loop {
+            let mut response = client
+                .query(
+                    "CREATE type::thing($table, 'lock') SET owner = $owner, acquired_at = time::now()",
+                )
+                .bind(("table", LOCK_TABLE))
+                .bind(("owner", owner.clone()))
+                .await?;
+            match response.take::<Option<Lock>>(0) {
+                Ok(_) => return Ok(owner),
+                Err(err) if !is_record_exists(&err) => return Err(err.into()),
+                Err(_) => {}
+            }
+            let holder: Option<Lock> = client
+                .query("SELECT owner FROM ONLY type::thing($table, 'lock')")
+                .bind(("table", LOCK_TABLE))
+                .await?
+                .take(0)?;
+            // Otherwise the lock was released in the meantime, try again
+            if let Some(holder) = holder {
+                return Err(MigrationError::Locked(holder.owner));
+            }
+        }
+    }
+
+    async fn unlock(&self, client: &SurrealClient, owner: &str) -> MigrationResult<()> {
+        client
+            .query("DELETE type::thing($table, 'lock') WHERE owner = $owner")
+            .bind(("table", LOCK_TABLE))
+            .bind(("owner", owner.to_string()))
+            .await?
+            .check()?;
+        Ok(())
+    }
+}
+
+impl Default for Migrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether the error is raised by creating a record which already exists
+fn is_record_exists(err: &surrealdb::Error) -> bool {
+    // Remote engines only forward the message
+    let message = err.to_string();
+    matches!(
+        err,
+        surrealdb::Error::Db(surrealdb::error::Db::RecordExists { .. })
+    ) || (message.contains("Database record `") && message.contains("already exists"))
+}
+
+/// Run a step, recording `migration` as applied when given
+///
+/// SQL steps are executed in the same transaction as the bookkeeping record.
+async fn run_step(
+    client: &SurrealClient,
+    step: &Step,
+    migration: Option<&Migration>,
+) -> MigrationResult<()> {
+    match step {
+        Step::Sql(sql) => {
+            let record = if migration.is_some() {
+                "CREATE type::thing($table, $version) SET version = $version, name = $name, applied_at = time::now();"
+            } else {
+                ""
+            };
+            let sql = format!(
+                "BEGIN TRANSACTION;\n{};\n{}\nCOMMIT TRANSACTION;",
+                sql.trim().trim_end_matches(';'),
+                record
+            );
+            let (version, name) = migration
+                .map(|migration| (migration.version, migration.name))
+                .unwrap_or_default();
+            client
+                .query(sql)
+                .bind(("table", MIGRATIONS_TABLE))
+                .bind(("version", version))
+                .bind(("name", name))
+                .await?
+                .check()?;
+        }
+        Step::Func(func) => {
+            func(client).await?;
+            if let Some(migration) = migration {
+                record(client, migration).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn record(client: &SurrealClient, migration: &Migration) -> MigrationResult<()> {
+    client
+        .query("CREATE type::thing($table, $version) SET version = $version, name = $name, applied_at = time::now()")
+        .bind(("table", MIGRATIONS_TABLE))
+        .bind(("version", migration.version))
+        .bind(("name", migration.name))
+        .await?
+        .check()?;
+    Ok(())
+}
+
+async fn forget(client: &SurrealClient, version: u64) -> MigrationResult<()> {
+    client
+        .query("DELETE type::thing($table, $version)")
+        .bind(("table", MIGRATIONS_TABLE))
+        .bind(("version", version))
+        .await?
+        .check()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::FieldSchema;
+
+    #[test]
+    fn test_duplicate_versions() {
+        let migrator = Migrator::new()
+            .register(Migration::sql(2, "second", "DEFINE TABLE b"))
+            .register(Migration::sql(1, "first", "DEFINE TABLE a"))
+            .register(Migration::sql(2, "again", "DEFINE TABLE c"));
+        assert!(matches!(
+            migrator.sorted(),
+            Err(MigrationError::DuplicateVersion(2))
+        ));
+    }
+
+    #[test]
+    fn test_sorted_versions() {
+        let migrator = Migrator::new()
+            .register(Migration::sql(3, "third", "DEFINE TABLE c"))
+            .register(Migration::sql(1, "first", "DEFINE TABLE a").down_sql("REMOVE TABLE a"));
+        let versions = migrator
+            .sorted()
+            .unwrap()
+            .iter()
+            .map(|migration| migration.version)
+            .collect::<Vec<_>>();
+        assert_eq!(versions, vec![1, 3]);
+        assert!(migrator.migrations()[1].is_reversible());
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_up_under_lock() {
+        let db = crate::testing::TestDb::new().await.unwrap();
+        let migrator = Migrator::new()
+            .schema(Schema::new().table(TableSchema::new("users").schemafull()))
+            .register(Migration::sql(
+                1,
+                "define_logs",
+                "DEFINE TABLE logs SCHEMALESS",
+            ));
+
+        db.query(
+            "CREATE type::thing($table, 'lock') SET owner = 'other', acquired_at = time::now()",
+        )
+        .bind(("table", LOCK_TABLE))
+        .await
+        .unwrap()
+        .check()
+        .unwrap();
+        match migrator.up(&db).await {
+            Err(MigrationError::Locked(owner)) => assert_eq!(owner, "other"),
+            result => panic!("unexpected result: {:?}", result),
+        }
+        let info = DbInfo::load(&db).await.unwrap();
+        assert!(!info.tables.contains_key("users"));
+
+        db.query("DELETE type::table($table)")
+            .bind(("table", LOCK_TABLE))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+        assert_eq!(migrator.up(&db).await.unwrap(), vec![1]);
+        let info = DbInfo::load(&db).await.unwrap();
+        assert!(info.tables.contains_key("users"));
+        assert!(info.tables.contains_key("logs"));
+        assert!(migrator.up(&db).await.unwrap().is_empty());
+    }
+
+    #[cfg(feature = "testing")]
+    #[tokio::test]
+    async fn test_lock_error() {
+        let db = crate::testing::TestDb::new().await.unwrap();
+        let sql = format!(
+            "DEFINE TABLE {table} SCHEMAFULL; DEFINE FIELD owner ON {table} TYPE int",
+            table = LOCK_TABLE
+        );
+        db.query(sql).await.unwrap().check().unwrap();
+        let result = Migrator::new().up(&db).await;
+        assert!(
+            matches!(result, Err(MigrationError::Database(_))),
+            "unexpected result: {:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let schema = Schema::new()
+            .table(
+                TableSchema::new("users")
+                    .schemafull()
+                    .field(FieldSchema::new("username", "string"))
+                    .field(FieldSchema::new("email", "string"))
+                    .index("username", true),
+            )
+            .table(TableSchema::new("logs"));
+
+        let mut info = DbInfo::default();
+        info.tables.insert(
+            "users".to_string(),
+            TableInfo {
+                definition: "DEFINE TABLE users TYPE NORMAL SCHEMAFULL PERMISSIONS NONE"
+                    .to_string(),
+                fields: BTreeMap::from([
+                    (
+                        "username".to_string(),
+                        "DEFINE FIELD username ON users TYPE int PERMISSIONS FULL".to_string(),
+                    ),
+                    (
+                        "nickname".to_string(),
+                        "DEFINE FIELD nickname ON users TYPE string PERMISSIONS FULL".to_string(),
+                    ),
+                ]),
+                indexes: BTreeMap::new(),
+            },
+        );
+        info.tables
+            .insert(MIGRATIONS_TABLE.to_string(), TableInfo::default());
+        info.tables
+            .insert("orphans".to_string(), TableInfo::default());
+
+        assert_eq!(
+            info.diff(&schema),
+            vec![
+                Drift::FieldMismatch {
+                    table: "users".to_string(),
+                    field: "username".to_string(),
+                    expected: "TYPE string".to_string(),
+                    actual: "DEFINE FIELD username ON users TYPE int PERMISSIONS FULL".to_string(),
+                },
+                Drift::MissingField {
+                    table: "users".to_string(),
+                    field: "email".to_string(),
+                },
+                Drift::UnexpectedField {
+                    table: "users".to_string(),
+                    field: "nickname".to_string(),
+                },
+                Drift::MissingIndex {
+                    table: "users".to_string(),
+                    index: "users_username_unique".to_string(),
+                },
+                Drift::MissingTable("logs".to_string()),
+                Drift::UnexpectedTable("orphans".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,282 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use surrealdb::{RecordId, RecordIdKey};
+
+use crate::field::Field;
+use crate::query::{Params, escape_field};
+use crate::{Condition, Error, Model, Operator, Order, Query, Result};
+
+/// One page of records, returned by [`Paginate::fetch`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor to pass to [`Paginate::after`] for the next page
+    pub next_cursor: Option<String>,
+    pub has_more: bool,
+    /// Number of matching records, only counted with [`Paginate::with_total`]
+    pub total: Option<u64>,
+}
+
+impl<T> Page<T> {
+    /// Convert the items, e.g. into the `Data` of their model
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            has_more: self.has_more,
+            total: self.total,
+        }
+    }
+}
+
+/// Sort key of a [`Paginate`], ties are broken by record id
+struct Key {
+    field: &'static str,
+    cast: Option<&'static str>,
+    order: Order,
+}
+
+impl Key {
+    fn compare(&self, op: Operator, value: serde_json::Value) -> Condition {
+        match self.cast {
+            Some(kind) => Condition::compare_cast(self.field, op, value, kind),
+            None => Condition::compare(self.field, op, value),
+        }
+    }
+}
+
+/// Position after the last record of a page: its sort key, if any, then the id key
+#[derive(Serialize, Deserialize)]
+struct Cursor(Vec<serde_json::Value>);
+
+impl Cursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&self.0).unwrap_or_default())
+    }
+
+    fn decode(cursor: &str) -> Result<Self> {
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .map(Cursor)
+            .ok_or_else(invalid_cursor)
+    }
+}
+
+fn invalid_cursor() -> Error {
+    Error::Validation("Invalid pagination cursor".to_string())
+}
+
+/// A [`Query`] returning one [`Page`] at a time using keyset pagination
+///
+/// Records are ordered by the sort key and then by id, the cursor of a page
+/// encodes both for its last record so that the next page starts right after
+/// it even if records are inserted or deleted in between.
+///
+/// ```ignore
+/// let page = Task::objects(db)
+///     .filter(Task::fields.project_id.eq(project.id))
+///     .paginate(20)
+///     .order_by(Task::fields.created_at, Order::Desc)
+///     .after(params.cursor.as_deref())
+///     .fetch()
+///     .await?;
+/// ```
+pub struct Paginate<'c, T> {
+    query: Query<'c, T>,
+    limit: u64,
+    key: Option<Key>,
+    after: Option<String>,
+    total: bool,
+}
+
+impl<'c, T> Query<'c, T>
+where
+    T: Model,
+{
+    /// Paginate matching records `limit` at a time, ignoring ordering and pagination
+    pub fn paginate(self, limit: u64) -> Paginate<'c, T> {
+        Paginate {
+            query: self,
+            limit,
+            key: None,
+            after: None,
+            total: false,
+        }
+    }
+}
+
+impl<T> Paginate<'_, T>
+where
+    T: Model,
+{
+    /// Sort by `field` instead of only by id
+    pub fn order_by<V>(mut self, field: Field<T, V>, order: Order) -> Self {
+        self.key = Some(Key {
+            field: field.name(),
+            cast: field.cast_kind(),
+            order,
+        });
+        self
+    }
+
+    /// Start after the record the cursor of a previous page points to
+    pub fn after(mut self, cursor: Option<&str>) -> Self {
+        self.after = cursor.map(str::to_string);
+        self
+    }
+
+    /// Also count all matching records into [`Page::total`]
+    pub fn with_total(mut self) -> Self {
+        self.total = true;
+        self
+    }
+
+    fn id_order(&self) -> Order {
+        self.key.as_ref().map(|key| key.order).unwrap_or(Order::Asc)
+    }
+
+    /// Condition selecting the records after `cursor`
+    fn after_condition(&self, cursor: Cursor) -> Result<Condition> {
+        let op = match self.id_order() {
+            Order::Asc => Operator::Gt,
+            Order::Desc => Operator::Lt,
+        };
+        let mut values = cursor.0.into_iter();
+        let key = match &self.key {
+            Some(key) => Some((key, values.next().ok_or_else(invalid_cursor)?)),
+            None => None,
+        };
+        let id = match values.next() {
+            Some(serde_json::Value::String(id)) => RecordIdKey::from(id),
+            Some(serde_json::Value::Number(id)) => {
+                RecordIdKey::from(id.as_i64().ok_or_else(invalid_cursor)?)
+            }
+            _ => return Err(invalid_cursor()),
+        };
+        let after_id = Condition::compare("id", op, RecordId::from((T::TABLE_NAME, id)));
+        Ok(match key {
+            Some((key, value)) => key
+                .compare(op, value.clone())
+                .or(key.compare(Operator::Eq, value).and(after_id)),
+            None => after_id,
+        })
+    }
+
+    fn render(&self, params: &mut Params) -> Result<String> {
+        let mut query = Query::<T>::new(self.query.client);
+        query.conditions = self.query.conditions.clone();
+        query.deleted = self.query.deleted;
+        if let Some(cursor) = &self.after {
+            query = query.filter(self.after_condition(Cursor::decode(cursor)?)?);
+        }
+        let cursor = match &self.key {
+            Some(key) => {
+                query = query.order_by(key.field, key.order);
+                format!("[{}, record::id(id)]", escape_field(key.field))
+            }
+            None => "[record::id(id)]".to_string(),
+        };
+        // One extra record tells whether there is a next page
+        let query = query.order_by("id", self.id_order()).limit(self.limit + 1);
+        Ok(format!(
+            "SELECT $this AS item, {} AS cursor FROM ({})",
+            cursor,
+            query.render_select(params)
+        ))
+    }
+
+    pub async fn fetch(self) -> Result<Page<T>> {
+        #[derive(Deserialize)]
+        #[serde(bound(deserialize = "T: Model"))]
+        struct Row<T> {
+            item: T,
+            cursor: Vec<serde_json::Value>,
+        }
+
+        let mut params = Params::default();
+        let sql = self.render(&mut params)?;
+        let mut rows: Vec<Row<T>> = self
+            .query
+            .client
+            .query(sql)
+            .bind(params.into_bindings()?)
+            .await?
+            .take(0)?;
+        let has_more = rows.len() as u64 > self.limit;
+        rows.truncate(self.limit as usize);
+        let next_cursor = match rows.last() {
+            Some(row) if has_more => Some(Cursor(row.cursor.clone()).encode()),
+            _ => None,
+        };
+        let total = match self.total {
+            true => Some(self.query.count().await?),
+            false => None,
+        };
+        Ok(Page {
+            items: rows.into_iter().map(|row| row.item).collect(),
+            next_cursor,
+            has_more,
+            total,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct AnyModel {}
+
+    impl Model for AnyModel {
+        const TABLE_NAME: &'static str = "any_model";
+        #[cfg(feature = "utoipa")]
+        type Data = ();
+        type Input = ();
+        type Patch = ();
+        fn table_name(&self) -> &'static str {
+            Self::TABLE_NAME
+        }
+        #[cfg(feature = "utoipa")]
+        fn into_data(self) -> Self::Data {}
+    }
+
+    #[test]
+    fn test_render_paginate() {
+        let client = surrealdb::Surreal::init();
+        let query = Query::<AnyModel>::new(&client).filter(Condition::gt("points", 0));
+        let mut params = Params::default();
+        assert_eq!(
+            query.paginate(10).render(&mut params).unwrap(),
+            "SELECT $this AS item, [record::id(id)] AS cursor FROM (SELECT * FROM type::table($table) WHERE points > $p0 ORDER BY id ASC LIMIT $limit)"
+        );
+
+        let cursor = Cursor(vec![5.into(), "abc".into()]).encode();
+        let paginate = Query::<AnyModel>::new(&client)
+            .paginate(10)
+            .order_by(Field::<AnyModel, i64>::new("points"), Order::Desc)
+            .after(Some(&cursor));
+        let mut params = Params::default();
+        assert_eq!(
+            paginate.render(&mut params).unwrap(),
+            "SELECT $this AS item, [points, record::id(id)] AS cursor FROM (SELECT * FROM type::table($table) WHERE (points < $p0 OR (points = $p1 AND id < $p2)) ORDER BY points DESC, id DESC LIMIT $limit)"
+        );
+    }
+
+    #[test]
+    fn test_invalid_cursor() {
+        let client = surrealdb::Surreal::init();
+        let paginate = Query::<AnyModel>::new(&client)
+            .paginate(10)
+            .after(Some("not a cursor"));
+        let mut params = Params::default();
+        assert!(matches!(
+            paginate.render(&mut params),
+            Err(Error::Validation(_))
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+pub use crate::Factory as FactoryTrait;
 pub use crate::Model as ModelTrait;
 pub use crate::SurrealClient;
-pub use merak_macros::Model;
+pub use merak_macros::{Factory, FieldKind, Model};
@@ -0,0 +1,801 @@
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+use futures::{StreamExt, future};
+use serde::{Deserialize, Serialize};
+use surrealdb::Value;
+
+use crate::fetch::Fetch;
+use crate::field::Link;
+use crate::live::{LiveStream, Notification};
+use crate::schema::DELETED_AT;
+use crate::{Model, Result, SurrealClient, Validate};
+
+/// Comparison operator used in a [`Condition`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Contains,
+    Inside,
+}
+
+impl Operator {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Operator::Eq => "=",
+            Operator::Ne => "!=",
+            Operator::Gt => ">",
+            Operator::Gte => ">=",
+            Operator::Lt => "<",
+            Operator::Lte => "<=",
+            Operator::Contains => "CONTAINS",
+            Operator::Inside => "INSIDE",
+        }
+    }
+}
+
+/// Sort direction used by [`Query::order_by`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+impl Order {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Order::Asc => "ASC",
+            Order::Desc => "DESC",
+        }
+    }
+}
+
+/// A value bound to a query parameter
+///
+/// Serialization errors are deferred until the query is executed so that
+/// conditions can be built without handling a `Result` at every step.
+#[derive(Debug, Clone)]
+pub struct Param(std::result::Result<Value, String>);
+
+impl Param {
+    pub(crate) fn new<V>(value: V) -> Self
+    where
+        V: Serialize + 'static,
+    {
+        Param(surrealdb::value::to_value(value).map_err(|err| err.to_string()))
+    }
+}
+
+/// Named parameters collected while rendering a query
+#[derive(Debug, Default)]
+pub(crate) struct Params {
+    values: BTreeMap<String, Value>,
+    next: usize,
+    error: Option<String>,
+}
+
+impl Params {
+    pub(crate) fn push(&mut self, param: &Param) -> String {
+        let name = format!("p{}", self.next);
+        self.next += 1;
+        match &param.0 {
+            Ok(value) => {
+                self.values.insert(name.clone(), value.clone());
+            }
+            Err(err) => {
+                self.error.get_or_insert_with(|| err.clone());
+                self.values.insert(name.clone(), Value::default());
+            }
+        }
+        format!("${}", name)
+    }
+
+    pub(crate) fn insert<V>(&mut self, name: &str, value: V)
+    where
+        V: Serialize + 'static,
+    {
+        let param = Param::new(value);
+        match param.0 {
+            Ok(value) => {
+                self.values.insert(name.to_string(), value);
+            }
+            Err(err) => {
+                self.error.get_or_insert(err);
+            }
+        }
+    }
+
+    /// Replace the parameters in `sql` by their SurrealQL literals
+    ///
+    /// Needed for `LIVE SELECT`, whose conditions are evaluated after the
+    /// request that bound the parameters has finished.
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn inline(self, sql: &str) -> surrealdb::Result<String> {
+        let values = self.into_bindings()?;
+        let mut inlined = String::with_capacity(sql.len());
+        let mut rest = sql;
+        while let Some(start) = rest.find('$') {
+            inlined.push_str(&rest[..start]);
+            let name = &rest[start + 1..];
+            let end = name
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(name.len());
+            match values.get(&name[..end]) {
+                Some(value) => inlined.push_str(&value.to_string()),
+                None => inlined.push_str(&rest[start..start + 1 + end]),
+            }
+            rest = &name[end..];
+        }
+        inlined.push_str(rest);
+        Ok(inlined)
+    }
+
+    #[allow(clippy::result_large_err)]
+    pub(crate) fn into_bindings(self) -> surrealdb::Result<BTreeMap<String, Value>> {
+        match self.error {
+            Some(err) => Err(surrealdb::error::Api::SerializeValue(err).into()),
+            None => Ok(self.values),
+        }
+    }
+}
+
+/// Escape a field path so it can be safely embedded in SurrealQL
+///
+/// Plain identifiers (optionally separated by `.`) are emitted verbatim,
+/// everything else is wrapped in backticks.
+pub(crate) fn escape_field(field: &str) -> String {
+    field
+        .split('.')
+        .map(|part| {
+            let plain = !part.is_empty()
+                && !part.starts_with(|c: char| c.is_ascii_digit())
+                && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+            if plain {
+                part.to_string()
+            } else {
+                format!("`{}`", part.replace('`', "\\`"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Statement inserting the records in `$data`, returning their ids
+pub(crate) fn render_insert<T: Model>() -> String {
+    // `INSERT INTO` does not accept `type::table`, the table name comes from
+    // the model so it is inlined
+    format!(
+        "INSERT INTO {} $data RETURN VALUE id",
+        escape_field(T::TABLE_NAME)
+    )
+}
+
+/// Statement deleting the single record `record`, only marking it for soft deleting models
+pub(crate) fn render_delete_record<T: Model>(record: &str) -> String {
+    if T::SOFT_DELETE {
+        format!(
+            "UPDATE {} SET {field} = time::now() WHERE {field} = NONE RETURN BEFORE",
+            record,
+            field = DELETED_AT
+        )
+    } else {
+        format!("DELETE {} RETURN BEFORE", record)
+    }
+}
+
+/// A filter expression used in the `WHERE` clause of a [`Query`]
+///
+/// Values are always sent as bound parameters and never interpolated into
+/// the query string.
+#[derive(Debug, Clone)]
+pub enum Condition {
+    Compare {
+        field: String,
+        op: Operator,
+        value: Param,
+        cast: Option<String>,
+    },
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    pub fn compare<V>(field: impl Into<String>, op: Operator, value: V) -> Self
+    where
+        V: Serialize + 'static,
+    {
+        Condition::Compare {
+            field: field.into(),
+            op,
+            value: Param::new(value),
+            cast: None,
+        }
+    }
+
+    /// Compare against a value cast to the SurrealQL type `kind` on the server
+    pub fn compare_cast<V>(
+        field: impl Into<String>,
+        op: Operator,
+        value: V,
+        kind: impl Into<String>,
+    ) -> Self
+    where
+        V: Serialize + 'static,
+    {
+        Condition::Compare {
+            field: field.into(),
+            op,
+            value: Param::new(value),
+            cast: Some(kind.into()),
+        }
+    }
+
+    pub fn eq<V: Serialize + 'static>(field: impl Into<String>, value: V) -> Self {
+        Self::compare(field, Operator::Eq, value)
+    }
+
+    pub fn ne<V: Serialize + 'static>(field: impl Into<String>, value: V) -> Self {
+        Self::compare(field, Operator::Ne, value)
+    }
+
+    pub fn gt<V: Serialize + 'static>(field: impl Into<String>, value: V) -> Self {
+        Self::compare(field, Operator::Gt, value)
+    }
+
+    pub fn gte<V: Serialize + 'static>(field: impl Into<String>, value: V) -> Self {
+        Self::compare(field, Operator::Gte, value)
+    }
+
+    pub fn lt<V: Serialize + 'static>(field: impl Into<String>, value: V) -> Self {
+        Self::compare(field, Operator::Lt, value)
+    }
+
+    pub fn lte<V: Serialize + 'static>(field: impl Into<String>, value: V) -> Self {
+        Self::compare(field, Operator::Lte, value)
+    }
+
+    pub fn contains<V: Serialize + 'static>(field: impl Into<String>, value: V) -> Self {
+        Self::compare(field, Operator::Contains, value)
+    }
+
+    pub fn inside<V: Serialize + 'static>(field: impl Into<String>, values: V) -> Self {
+        Self::compare(field, Operator::Inside, values)
+    }
+
+    /// Combine with another condition, both must match
+    pub fn and(self, other: Condition) -> Self {
+        match self {
+            Condition::And(mut conditions) => {
+                conditions.push(other);
+                Condition::And(conditions)
+            }
+            condition => Condition::And(vec![condition, other]),
+        }
+    }
+
+    /// Combine with another condition, either may match
+    pub fn or(self, other: Condition) -> Self {
+        match self {
+            Condition::Or(mut conditions) => {
+                conditions.push(other);
+                Condition::Or(conditions)
+            }
+            condition => Condition::Or(vec![condition, other]),
+        }
+    }
+
+    /// Negate the condition
+    #[allow(clippy::should_implement_trait)]
+    pub fn not(self) -> Self {
+        Condition::Not(Box::new(self))
+    }
+
+    pub(crate) fn render(&self, params: &mut Params) -> String {
+        match self {
+            Condition::Compare {
+                field,
+                op,
+                value,
+                cast,
+            } => {
+                let param = params.push(value);
+                let param = match cast {
+                    Some(kind) => format!("<{}>{}", kind, param),
+                    None => param,
+                };
+                format!("{} {} {}", escape_field(field), op.as_str(), param)
+            }
+            Condition::And(conditions) => Self::render_group(conditions, "AND", params),
+            Condition::Or(conditions) => Self::render_group(conditions, "OR", params),
+            Condition::Not(condition) => format!("!({})", condition.render(params)),
+        }
+    }
+
+    fn render_group(conditions: &[Condition], joiner: &str, params: &mut Params) -> String {
+        match conditions {
+            [] => "true".to_string(),
+            [condition] => condition.render(params),
+            conditions => {
+                let rendered = conditions
+                    .iter()
+                    .map(|condition| condition.render(params))
+                    .collect::<Vec<_>>()
+                    .join(&format!(" {} ", joiner));
+                format!("({})", rendered)
+            }
+        }
+    }
+}
+
+/// Which records of a soft deleting model a [`Query`] matches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Deleted {
+    Exclude,
+    Include,
+    Only,
+}
+
+/// A composable `SELECT` query over the table of model `T`
+///
+/// Created from [`Objects::query`](crate::Objects::query) or
+/// [`Objects::filter`](crate::Objects::filter).
+pub struct Query<'c, T> {
+    pub(crate) client: &'c SurrealClient,
+    pub(crate) conditions: Vec<Condition>,
+    order: Vec<(String, Order)>,
+    limit: Option<u64>,
+    start: Option<u64>,
+    pub(crate) deleted: Deleted,
+    _model: PhantomData<T>,
+}
+
+impl<'c, T> Query<'c, T>
+where
+    T: Model,
+{
+    pub fn new(client: &'c SurrealClient) -> Self {
+        Query {
+            client,
+            conditions: vec![],
+            order: vec![],
+            limit: None,
+            start: None,
+            deleted: Deleted::Exclude,
+            _model: PhantomData,
+        }
+    }
+
+    /// Add a condition, multiple filters are combined with `AND`
+    pub fn filter(mut self, condition: Condition) -> Self {
+        self.conditions.push(condition);
+        self
+    }
+
+    pub fn order_by(mut self, field: impl Into<String>, order: Order) -> Self {
+        self.order.push((field.into(), order));
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn start(mut self, start: u64) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Also match soft deleted records
+    pub fn with_deleted(mut self) -> Self {
+        self.deleted = Deleted::Include;
+        self
+    }
+
+    /// Only match soft deleted records
+    pub fn only_deleted(mut self) -> Self {
+        self.deleted = Deleted::Only;
+        self
+    }
+
+    /// Load the records linked by `link` together with the matching records
+    ///
+    /// Uses a single `FETCH` instead of one select per record.
+    pub fn fetch<R: Model>(self, link: Link<T, R>) -> Fetch<'c, T, R> {
+        Fetch::new(self, link)
+    }
+
+    pub(crate) fn render_where(&self, params: &mut Params) -> String {
+        let mut rendered = self
+            .conditions
+            .iter()
+            .map(|condition| condition.render(params))
+            .collect::<Vec<_>>();
+        if T::SOFT_DELETE {
+            match self.deleted {
+                Deleted::Exclude => rendered.push(format!("{} = NONE", DELETED_AT)),
+                Deleted::Include => {}
+                Deleted::Only => rendered.push(format!("{} != NONE", DELETED_AT)),
+            }
+        }
+        if rendered.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", rendered.join(" AND "))
+        }
+    }
+
+    pub(crate) fn render_select(&self, params: &mut Params) -> String {
+        params.insert("table", T::TABLE_NAME);
+        let mut sql = format!(
+            "SELECT * FROM type::table($table){}",
+            self.render_where(params)
+        );
+        if !self.order.is_empty() {
+            let order = self
+                .order
+                .iter()
+                .map(|(field, order)| format!("{} {}", escape_field(field), order.as_str()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            sql.push_str(&format!(" ORDER BY {}", order));
+        }
+        if let Some(limit) = self.limit {
+            params.insert("limit", limit);
+            sql.push_str(" LIMIT $limit");
+        }
+        if let Some(start) = self.start {
+            params.insert("start", start);
+            sql.push_str(" START $start");
+        }
+        sql
+    }
+
+    pub(crate) fn render_live(&self, params: &mut Params) -> String {
+        // `LIVE SELECT` does not accept `type::table`, the table name comes
+        // from the model so it is inlined
+        format!(
+            "LIVE SELECT * FROM {}{}",
+            escape_field(T::TABLE_NAME),
+            self.render_where(params)
+        )
+    }
+
+    pub(crate) fn render_count(&self, params: &mut Params) -> String {
+        params.insert("table", T::TABLE_NAME);
+        format!(
+            "SELECT count() FROM type::table($table){} GROUP ALL",
+            self.render_where(params)
+        )
+    }
+
+    pub(crate) fn render_update(&self, data: &Param, params: &mut Params) -> String {
+        params.insert("table", T::TABLE_NAME);
+        let data = params.push(data);
+        format!(
+            "UPDATE type::table($table) MERGE {}{} RETURN VALUE id",
+            data,
+            self.render_where(params)
+        )
+    }
+
+    /// Delete statement returning `output`, like `BEFORE` or `VALUE id`
+    pub(crate) fn render_delete(&self, params: &mut Params, output: &str) -> String {
+        if T::SOFT_DELETE {
+            params.insert("table", T::TABLE_NAME);
+            // Records deleted before keep their original deletion time
+            return format!(
+                "UPDATE type::table($table) SET {field} = {field} OR time::now(){} RETURN {}",
+                self.render_where(params),
+                output,
+                field = DELETED_AT
+            );
+        }
+        self.render_force_delete(params, output)
+    }
+
+    pub(crate) fn render_force_delete(&self, params: &mut Params, output: &str) -> String {
+        params.insert("table", T::TABLE_NAME);
+        format!(
+            "DELETE FROM type::table($table){} RETURN {}",
+            self.render_where(params),
+            output
+        )
+    }
+
+    /// Fetch all matching records
+    pub async fn all(&self) -> Result<Vec<T>> {
+        let mut params = Params::default();
+        let sql = self.render_select(&mut params);
+        Ok(self
+            .client
+            .query(sql)
+            .bind(params.into_bindings()?)
+            .await?
+            .take(0)?)
+    }
+
+    /// Fetch the first matching record
+    pub async fn first(mut self) -> Result<Option<T>> {
+        self.limit = Some(1);
+        let mut params = Params::default();
+        let sql = self.render_select(&mut params);
+        Ok(self
+            .client
+            .query(sql)
+            .bind(params.into_bindings()?)
+            .await?
+            .take(0)?)
+    }
+
+    /// Count matching records, ignoring ordering and pagination
+    pub async fn count(&self) -> Result<u64> {
+        #[derive(Deserialize)]
+        struct Count {
+            count: u64,
+        }
+
+        let mut params = Params::default();
+        let sql = self.render_count(&mut params);
+        let count: Option<Count> = self
+            .client
+            .query(sql)
+            .bind(params.into_bindings()?)
+            .await?
+            .take(0)?;
+        Ok(count.map(|count| count.count).unwrap_or(0))
+    }
+
+    /// Check whether any record matches
+    pub async fn exists(&self) -> Result<bool> {
+        Ok(self.count().await? > 0)
+    }
+
+    /// Watch matching records, ignoring ordering and pagination
+    ///
+    /// ```ignore
+    /// let mut changes = Task::objects(db)
+    ///     .filter(Task::fields.project_id.eq(project.id))
+    ///     .live()
+    ///     .await?;
+    /// while let Some(notification) = changes.next().await {
+    ///     let Notification { action, data } = notification?;
+    /// }
+    /// ```
+    pub async fn live(&self) -> Result<LiveStream<T>>
+    where
+        T: Unpin + Send + 'static,
+    {
+        let mut params = Params::default();
+        let sql = self.render_live(&mut params);
+        let mut response = self.client.query(params.inline(&sql)?).await?;
+        let stream = response.stream::<surrealdb::Notification<T>>(0)?;
+        Ok(stream
+            .filter_map(|notification| {
+                future::ready(match notification {
+                    Ok(notification) => Notification::from_surreal(notification).map(Ok),
+                    Err(err) => Some(Err(err.into())),
+                })
+            })
+            .boxed())
+    }
+
+    /// Delete all matching records, returning them as they were before deletion
+    ///
+    /// Records of soft deleting models are only marked as deleted.
+    pub async fn delete(&self) -> Result<Vec<T>> {
+        let mut params = Params::default();
+        let sql = self.render_delete(&mut params, "BEFORE");
+        Ok(self
+            .client
+            .query(sql)
+            .bind(params.into_bindings()?)
+            .await?
+            .take(0)?)
+    }
+
+    /// Permanently delete all matching records, even for soft deleting models
+    pub async fn force_delete(&self) -> Result<Vec<T>> {
+        let mut params = Params::default();
+        let sql = self.render_force_delete(&mut params, "BEFORE");
+        Ok(self
+            .client
+            .query(sql)
+            .bind(params.into_bindings()?)
+            .await?
+            .take(0)?)
+    }
+
+    /// Update the fields set in `patch` on all matching records, returning how many were updated
+    ///
+    /// The version of versioned models is incremented but not checked.
+    pub async fn update(&self, patch: T::Patch) -> Result<u64> {
+        patch.validate()?;
+        let mut params = Params::default();
+        let sql = self.render_update(&Param::new(patch), &mut params);
+        self.write_count(&sql, params).await
+    }
+
+    /// Delete all matching records like [`delete`](Query::delete), returning how many were deleted
+    pub async fn delete_count(&self) -> Result<u64> {
+        let mut params = Params::default();
+        let sql = self.render_delete(&mut params, "VALUE id");
+        self.write_count(&sql, params).await
+    }
+
+    /// Run a write returning the ids of the written records and count them
+    async fn write_count(&self, sql: &str, params: Params) -> Result<u64> {
+        let count: Option<u64> = self
+            .client
+            .query(format!("array::len(({}))", sql))
+            .bind(params.into_bindings()?)
+            .await?
+            .take(0)?;
+        Ok(count.unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct AnyModel {}
+
+    impl Model for AnyModel {
+        const TABLE_NAME: &'static str = "any_model";
+        #[cfg(feature = "utoipa")]
+        type Data = ();
+        type Input = ();
+        type Patch = ();
+        fn table_name(&self) -> &'static str {
+            Self::TABLE_NAME
+        }
+        #[cfg(feature = "utoipa")]
+        fn into_data(self) -> Self::Data {}
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct SoftModel {}
+
+    impl Model for SoftModel {
+        const TABLE_NAME: &'static str = "soft_model";
+        #[cfg(feature = "utoipa")]
+        type Data = ();
+        type Input = ();
+        type Patch = ();
+        const SOFT_DELETE: bool = true;
+        fn table_name(&self) -> &'static str {
+            Self::TABLE_NAME
+        }
+        #[cfg(feature = "utoipa")]
+        fn into_data(self) -> Self::Data {}
+    }
+
+    fn client() -> SurrealClient {
+        surrealdb::Surreal::init()
+    }
+
+    #[test]
+    fn test_render_select() {
+        let client = client();
+        let query = Query::<AnyModel>::new(&client)
+            .filter(Condition::eq("username", "merak").or(Condition::eq("email", "merak")))
+            .filter(Condition::compare_cast(
+                "born",
+                Operator::Gt,
+                "2000-01-01T00:00:00Z",
+                "datetime",
+            ))
+            .order_by("created_at", Order::Desc)
+            .limit(10)
+            .start(20);
+        let mut params = Params::default();
+        let sql = query.render_select(&mut params);
+        assert_eq!(
+            sql,
+            "SELECT * FROM type::table($table) WHERE (username = $p0 OR email = $p1) AND born > <datetime>$p2 ORDER BY created_at DESC LIMIT $limit START $start"
+        );
+        let bindings = params.into_bindings().unwrap();
+        assert_eq!(bindings.len(), 6);
+    }
+
+    #[test]
+    fn test_render_live() {
+        let client = client();
+        let query = Query::<AnyModel>::new(&client)
+            .filter(Condition::eq("project", "merak"))
+            .order_by("created_at", Order::Desc)
+            .limit(10);
+        let mut params = Params::default();
+        let sql = query.render_live(&mut params);
+        assert_eq!(sql, "LIVE SELECT * FROM any_model WHERE project = $p0");
+        assert_eq!(
+            params.inline(&sql).unwrap(),
+            "LIVE SELECT * FROM any_model WHERE project = 'merak'"
+        );
+    }
+
+    #[test]
+    fn test_render_count() {
+        let client = client();
+        let query = Query::<AnyModel>::new(&client).filter(Condition::ne("name", "merak").not());
+        let mut params = Params::default();
+        let sql = query.render_count(&mut params);
+        assert_eq!(
+            sql,
+            "SELECT count() FROM type::table($table) WHERE !(name != $p0) GROUP ALL"
+        );
+    }
+
+    #[test]
+    fn test_render_delete() {
+        let client = client();
+        let query = Query::<AnyModel>::new(&client).filter(Condition::lt("expires_at", 0));
+        let mut params = Params::default();
+        let sql = query.render_delete(&mut params, "BEFORE");
+        assert_eq!(
+            sql,
+            "DELETE FROM type::table($table) WHERE expires_at < $p0 RETURN BEFORE"
+        );
+    }
+
+    #[test]
+    fn test_render_bulk_writes() {
+        let client = client();
+        let query = Query::<AnyModel>::new(&client).filter(Condition::eq("status", "open"));
+        let mut params = Params::default();
+        let data = Param::new(serde_json::json!({ "status": "done" }));
+        assert_eq!(
+            query.render_update(&data, &mut params),
+            "UPDATE type::table($table) MERGE $p0 WHERE status = $p1 RETURN VALUE id"
+        );
+        assert_eq!(
+            query.render_delete(&mut params, "VALUE id"),
+            "DELETE FROM type::table($table) WHERE status = $p2 RETURN VALUE id"
+        );
+        assert_eq!(
+            render_insert::<AnyModel>(),
+            "INSERT INTO any_model $data RETURN VALUE id"
+        );
+    }
+
+    #[test]
+    fn test_soft_delete() {
+        let client = client();
+        let query = Query::<SoftModel>::new(&client).filter(Condition::eq("name", "merak"));
+        let mut params = Params::default();
+        assert_eq!(
+            query.render_select(&mut params),
+            "SELECT * FROM type::table($table) WHERE name = $p0 AND deleted_at = NONE"
+        );
+        assert_eq!(
+            query.render_delete(&mut params, "BEFORE"),
+            "UPDATE type::table($table) SET deleted_at = deleted_at OR time::now() WHERE name = $p1 AND deleted_at = NONE RETURN BEFORE"
+        );
+        let query = query.only_deleted();
+        assert_eq!(
+            query.render_force_delete(&mut params, "BEFORE"),
+            "DELETE FROM type::table($table) WHERE name = $p2 AND deleted_at != NONE RETURN BEFORE"
+        );
+        let query = Query::<SoftModel>::new(&client).with_deleted();
+        assert_eq!(
+            query.render_count(&mut params),
+            "SELECT count() FROM type::table($table) GROUP ALL"
+        );
+    }
+
+    #[test]
+    fn test_escape_field() {
+        assert_eq!(escape_field("user_id"), "user_id");
+        assert_eq!(escape_field("profile.name"), "profile.name");
+        assert_eq!(escape_field("name; DROP"), "`name; DROP`");
+        assert_eq!(escape_field("1st"), "`1st`");
+    }
+}
@@ -0,0 +1,108 @@
+use serde::Serialize;
+
+use crate::schema::{Schema, TableSchema};
+
+#[doc(hidden)]
+pub use inventory;
+
+/// Description of a model, registered by the `Model` derive
+///
+/// ```ignore
+/// for model in merak_core::registry::models() {
+///     println!("{} ({} fields)", model.table_name, model.fields.len());
+/// }
+/// ```
+#[derive(Debug, Serialize)]
+pub struct ModelMeta {
+    /// Name of the Rust type
+    pub name: &'static str,
+    pub table_name: &'static str,
+    /// Name of the `#[field(primary)]` field
+    pub primary_key: Option<&'static str>,
+    /// Stored fields, in declaration order
+    pub fields: &'static [FieldMeta],
+    /// Tables of the `in` and `out` records of an edge model
+    pub relation: Option<(&'static str, &'static str)>,
+    pub soft_delete: bool,
+    #[serde(skip)]
+    pub schema: fn() -> TableSchema,
+}
+
+impl ModelMeta {
+    /// Field stored under `name`
+    pub fn field(&self, name: &str) -> Option<&'static FieldMeta> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Fields linking to the records of another table
+    pub fn foreign_keys(&self) -> impl Iterator<Item = &'static FieldMeta> {
+        self.fields
+            .iter()
+            .filter(|field| field.foreign_key.is_some())
+    }
+
+    /// Fields with a single-field index, unique or not
+    pub fn indexes(&self) -> impl Iterator<Item = &'static FieldMeta> {
+        self.fields
+            .iter()
+            .filter(|field| field.unique || field.index)
+    }
+
+    pub fn created_at(&self) -> Option<&'static FieldMeta> {
+        self.fields.iter().find(|field| field.created_at)
+    }
+
+    pub fn updated_at(&self) -> Option<&'static FieldMeta> {
+        self.fields.iter().find(|field| field.updated_at)
+    }
+
+    pub fn version(&self) -> Option<&'static FieldMeta> {
+        self.fields.iter().find(|field| field.version)
+    }
+
+    /// Tables, fields and indexes of the model, see [`Model::schema`](crate::Model::schema)
+    pub fn table_schema(&self) -> TableSchema {
+        (self.schema)()
+    }
+}
+
+/// Description of a stored field of a [`ModelMeta`]
+#[derive(Debug, Serialize)]
+pub struct FieldMeta {
+    /// Name in the database, after `#[serde(rename)]`
+    pub name: &'static str,
+    /// Rust type as written in the struct
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    /// Table of the linked records, for `#[field(foreign_key)]` and `Id<M>` fields
+    pub foreign_key: Option<&'static str>,
+    pub primary: bool,
+    pub unique: bool,
+    pub index: bool,
+    pub created_at: bool,
+    pub updated_at: bool,
+    pub version: bool,
+}
+
+inventory::collect!(ModelMeta);
+
+/// All models compiled into the binary, ordered by table name
+pub fn models() -> Vec<&'static ModelMeta> {
+    let mut models = inventory::iter::<ModelMeta>.into_iter().collect::<Vec<_>>();
+    models.sort_by_key(|model| model.table_name);
+    models
+}
+
+/// Model stored in `table_name`
+pub fn model(table_name: &str) -> Option<&'static ModelMeta> {
+    inventory::iter::<ModelMeta>
+        .into_iter()
+        .find(|model| model.table_name == table_name)
+}
+
+/// Schema of all models compiled into the binary, see [`models`]
+pub fn schema() -> Schema {
+    models().into_iter().fold(Schema::new(), |schema, model| {
+        schema.table(model.table_schema())
+    })
+}
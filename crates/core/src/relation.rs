@@ -0,0 +1,92 @@
+use serde::Serialize;
+use surrealdb::RecordId;
+
+use crate::query::{Condition, Param, Params};
+use crate::{IntoRecordId, Model, Objects, Query, Result};
+
+/// A model stored in an edge table linking records of `In` to records of `Out`
+///
+/// Implemented by the `Model` derive for structs annotated with
+/// `#[model(relation(in = User, out = Project))]`. Fields of the struct are
+/// the edge properties, the endpoints are available as `in` and `out`.
+pub trait Relation: Model {
+    type In: Model;
+    type Out: Model;
+}
+
+impl<'c, E, I> Objects<'c, E, I>
+where
+    E: Relation,
+    I: Serialize + 'static,
+{
+    /// Create an edge `from->E->to` with the given properties
+    pub async fn relate(
+        &self,
+        from: impl IntoRecordId<E::In>,
+        to: impl IntoRecordId<E::Out>,
+        data: I,
+    ) -> Result<Option<E>> {
+        let mut params = Params::default();
+        let from = params.push(&Param::new(from.into_record_id()));
+        let to = params.push(&Param::new(to.into_record_id()));
+        let content = params.push(&Param::new(data));
+        let sql = format!(
+            "RELATE {}->(type::table($table))->{} CONTENT {}",
+            from, to, content
+        );
+        params.insert("table", E::TABLE_NAME);
+        let mut response = self.client.query(sql).bind(params.into_bindings()?).await?;
+        Ok(response.take(0)?)
+    }
+
+    /// Delete all edges from `from` to `to`, returning them as they were before deletion
+    pub async fn unrelate(
+        &self,
+        from: impl IntoRecordId<E::In>,
+        to: impl IntoRecordId<E::Out>,
+    ) -> Result<Vec<E>> {
+        self.edges_from(from)
+            .filter(Condition::eq("out", to.into_record_id()))
+            .delete()
+            .await
+    }
+
+    /// Query the edges leaving `from`
+    pub fn edges_from(&self, from: impl IntoRecordId<E::In>) -> Query<'c, E> {
+        self.filter(Condition::eq("in", from.into_record_id()))
+    }
+
+    /// Query the edges pointing to `to`
+    pub fn edges_to(&self, to: impl IntoRecordId<E::Out>) -> Query<'c, E> {
+        self.filter(Condition::eq("out", to.into_record_id()))
+    }
+
+    /// Fetch the distinct records reached from `from` through this edge
+    pub async fn outgoing(&self, from: impl IntoRecordId<E::In>) -> Result<Vec<E::Out>> {
+        self.traverse("out", "in", from.into_record_id()).await
+    }
+
+    /// Fetch the distinct records reaching `to` through this edge
+    pub async fn incoming(&self, to: impl IntoRecordId<E::Out>) -> Result<Vec<E::In>> {
+        self.traverse("in", "out", to.into_record_id()).await
+    }
+
+    async fn traverse<T: Model>(
+        &self,
+        target: &str,
+        source: &str,
+        record: RecordId,
+    ) -> Result<Vec<T>> {
+        let sql = format!(
+            "SELECT * FROM array::distinct((SELECT VALUE {} FROM type::table($table) WHERE {} = $record))",
+            target, source
+        );
+        let mut response = self
+            .client
+            .query(sql)
+            .bind(("table", E::TABLE_NAME))
+            .bind(("record", record))
+            .await?;
+        Ok(response.take(0)?)
+    }
+}
@@ -0,0 +1,374 @@
+use std::marker::PhantomData;
+
+use crate::{Model, SurrealClient};
+
+/// Field holding the deletion time of soft deleted records
+pub(crate) const DELETED_AT: &str = "deleted_at";
+
+/// Definition of a single field, rendered as `DEFINE FIELD`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldSchema {
+    pub name: &'static str,
+    pub kind: String,
+    pub cast: bool,
+    /// Keep nested fields of objects, which `SCHEMAFULL` tables drop otherwise
+    pub flexible: bool,
+    /// Expression computing the value on every write
+    pub value: Option<String>,
+}
+
+impl FieldSchema {
+    pub fn new(name: &'static str, kind: impl Into<String>) -> Self {
+        FieldSchema {
+            name,
+            kind: kind.into(),
+            cast: false,
+            flexible: false,
+            value: None,
+        }
+    }
+
+    /// Enforce the type by casting on write instead of a `TYPE` clause
+    ///
+    /// SurrealDB checks `TYPE` before `VALUE`, so values that serialize as
+    /// strings (like `chrono::DateTime`) are rejected by `TYPE datetime`.
+    pub fn cast(mut self) -> Self {
+        self.cast = true;
+        self
+    }
+
+    /// Keep nested fields of the value, required for embedded objects in `SCHEMAFULL` tables
+    pub fn flexible(mut self) -> Self {
+        self.flexible = true;
+        self
+    }
+
+    /// Compute the value with a SurrealQL expression instead of taking the written one
+    ///
+    /// `$before` refers to the previous value, so `$before OR time::now()`
+    /// keeps the first value ever written.
+    pub fn value(mut self, expr: impl Into<String>) -> Self {
+        self.value = Some(expr.into());
+        self
+    }
+
+    pub(crate) fn definition(&self) -> String {
+        let flexible = if self.flexible { "FLEXIBLE " } else { "" };
+        match (&self.value, self.cast) {
+            (Some(value), true) => format!("VALUE <{}> ({})", self.kind, value),
+            (Some(value), false) => format!("{}TYPE {} VALUE {}", flexible, self.kind, value),
+            (None, true) => format!("VALUE <{}> $value", self.kind),
+            (None, false) => format!("{}TYPE {}", flexible, self.kind),
+        }
+    }
+}
+
+/// Types stored in model fields with a SurrealQL type of their own
+///
+/// Derive it with `#[derive(FieldKind)]`: enums of unit variants become a
+/// union of their serialized names, structs become flexible objects. Fields
+/// of other types not known to the `Model` derive are defined as `any`.
+///
+/// ```ignore
+/// #[derive(FieldKind, Serialize, Deserialize)]
+/// #[serde(rename_all = "snake_case")]
+/// enum Status { Todo, InProgress, Done }
+///
+/// assert_eq!(Status::kind(), "'todo' | 'in_progress' | 'done'");
+/// ```
+pub trait FieldKind {
+    /// Whether nested fields must be kept, see [`FieldSchema::flexible`]
+    const FLEXIBLE: bool = false;
+
+    fn kind() -> String;
+}
+
+/// Resolves the SurrealQL type of `T` in code generated by the `Model` derive
+///
+/// `(&&KindOf::<T>::new()).kind()` picks the [`FieldKind`] impl when `T` has
+/// one and falls back to a flexible `any` otherwise.
+#[doc(hidden)]
+pub struct KindOf<T: ?Sized>(PhantomData<T>);
+
+impl<T: ?Sized> KindOf<T> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        KindOf(PhantomData)
+    }
+}
+
+#[doc(hidden)]
+pub trait DeclaredKind {
+    fn kind(&self) -> (String, bool);
+}
+
+impl<T: FieldKind + ?Sized> DeclaredKind for &KindOf<T> {
+    fn kind(&self) -> (String, bool) {
+        (T::kind(), T::FLEXIBLE)
+    }
+}
+
+#[doc(hidden)]
+pub trait AnyKind {
+    fn kind(&self) -> (String, bool);
+}
+
+impl<T: ?Sized> AnyKind for KindOf<T> {
+    fn kind(&self) -> (String, bool) {
+        ("any".to_string(), true)
+    }
+}
+
+/// Definition of an index over one or more fields, rendered as `DEFINE INDEX`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexSchema {
+    pub name: String,
+    pub fields: Vec<&'static str>,
+    pub unique: bool,
+}
+
+impl IndexSchema {
+    pub fn new(name: impl Into<String>, fields: Vec<&'static str>, unique: bool) -> Self {
+        IndexSchema {
+            name: name.into(),
+            fields,
+            unique,
+        }
+    }
+}
+
+/// Schema of a model table, generated by the `Model` derive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableSchema {
+    pub table: &'static str,
+    pub schemafull: bool,
+    /// Tables of the `in` and `out` records of an edge table
+    pub relation: Option<(&'static str, &'static str)>,
+    pub fields: Vec<FieldSchema>,
+    pub indexes: Vec<IndexSchema>,
+}
+
+impl TableSchema {
+    /// Create a schemaless table definition without any fields
+    pub fn new(table: &'static str) -> Self {
+        TableSchema {
+            table,
+            schemafull: false,
+            relation: None,
+            fields: vec![],
+            indexes: vec![],
+        }
+    }
+
+    pub fn schemafull(mut self) -> Self {
+        self.schemafull = true;
+        self
+    }
+
+    /// Define the table as an edge from records of `from` to records of `to`
+    pub fn relation(mut self, from: &'static str, to: &'static str) -> Self {
+        self.relation = Some((from, to));
+        self
+    }
+
+    /// Add the `deleted_at` field used by soft deletes
+    pub fn soft_delete(self) -> Self {
+        self.field(FieldSchema::new(DELETED_AT, "option<datetime>").cast())
+    }
+
+    pub fn field(mut self, field: FieldSchema) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Add a single-field index named `<table>_<field>_index` or `<table>_<field>_unique`
+    pub fn index(mut self, field: &'static str, unique: bool) -> Self {
+        let suffix = if unique { "unique" } else { "index" };
+        let name = format!("{}_{}_{}", self.table, field, suffix);
+        self.indexes
+            .push(IndexSchema::new(name, vec![field], unique));
+        self
+    }
+
+    /// Table kind and mode as rendered after the table name in `DEFINE TABLE`
+    pub(crate) fn definition(&self) -> String {
+        let mode = if self.schemafull {
+            "SCHEMAFULL"
+        } else {
+            "SCHEMALESS"
+        };
+        match self.relation {
+            Some((from, to)) => format!("TYPE RELATION IN {} OUT {} {}", from, to, mode),
+            None => mode.to_string(),
+        }
+    }
+
+    /// Render the SurrealQL statements defining this table
+    pub fn statements(&self) -> Vec<String> {
+        let mut statements = vec![format!(
+            "DEFINE TABLE OVERWRITE {} {}",
+            self.table,
+            self.definition()
+        )];
+        statements.extend(self.fields.iter().map(|field| {
+            format!(
+                "DEFINE FIELD OVERWRITE {} ON TABLE {} {}",
+                field.name,
+                self.table,
+                field.definition()
+            )
+        }));
+        statements.extend(self.indexes.iter().map(|index| {
+            format!(
+                "DEFINE INDEX OVERWRITE {} ON TABLE {} FIELDS {}{}",
+                index.name,
+                self.table,
+                index.fields.join(", "),
+                if index.unique { " UNIQUE" } else { "" }
+            )
+        }));
+        statements
+    }
+}
+
+/// A set of model schemas that can be applied to a database at once
+///
+/// ```ignore
+/// Schema::new()
+///     .register::<User>()
+///     .register::<AuthSession>()
+///     .apply(&db)
+///     .await?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    tables: Vec<TableSchema>,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<M: Model>(mut self) -> Self {
+        self.tables.push(M::schema());
+        self
+    }
+
+    /// Add a table which is not backed by a model
+    pub fn table(mut self, table: TableSchema) -> Self {
+        self.tables.push(table);
+        self
+    }
+
+    pub fn tables(&self) -> &[TableSchema] {
+        &self.tables
+    }
+
+    pub fn statements(&self) -> Vec<String> {
+        self.tables
+            .iter()
+            .flat_map(TableSchema::statements)
+            .collect()
+    }
+
+    /// Define all registered tables, fields and indexes
+    pub async fn apply(&self, client: &SurrealClient) -> surrealdb::Result<()> {
+        let statements = self.statements();
+        if statements.is_empty() {
+            return Ok(());
+        }
+        client.query(statements.join(";\n")).await?.check()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_statements() {
+        let schema = TableSchema::new("users")
+            .schemafull()
+            .field(FieldSchema::new("username", "string"))
+            .field(FieldSchema::new("owner", "option<record<users>>"))
+            .field(FieldSchema::new("created_at", "datetime").cast())
+            .field(
+                FieldSchema::new("updated_at", "datetime")
+                    .cast()
+                    .value("time::now()"),
+            )
+            .field(FieldSchema::new("seen_at", "datetime").value("$before OR time::now()"))
+            .index("username", true)
+            .index("owner", false);
+        assert_eq!(
+            schema.statements(),
+            vec![
+                "DEFINE TABLE OVERWRITE users SCHEMAFULL",
+                "DEFINE FIELD OVERWRITE username ON TABLE users TYPE string",
+                "DEFINE FIELD OVERWRITE owner ON TABLE users TYPE option<record<users>>",
+                "DEFINE FIELD OVERWRITE created_at ON TABLE users VALUE <datetime> $value",
+                "DEFINE FIELD OVERWRITE updated_at ON TABLE users VALUE <datetime> (time::now())",
+                "DEFINE FIELD OVERWRITE seen_at ON TABLE users TYPE datetime VALUE $before OR time::now()",
+                "DEFINE INDEX OVERWRITE users_username_unique ON TABLE users FIELDS username UNIQUE",
+                "DEFINE INDEX OVERWRITE users_owner_index ON TABLE users FIELDS owner",
+            ]
+        );
+    }
+
+    #[test]
+    // The double reference is what selects the `FieldKind` impl
+    #[allow(clippy::needless_borrow)]
+    fn test_field_kind() {
+        use super::{AnyKind as _, DeclaredKind as _};
+
+        struct Address;
+
+        impl FieldKind for Address {
+            const FLEXIBLE: bool = true;
+
+            fn kind() -> String {
+                "object".to_string()
+            }
+        }
+
+        assert_eq!(
+            (&&KindOf::<Address>::new()).kind(),
+            ("object".to_string(), true)
+        );
+        assert_eq!(
+            (&&KindOf::<serde_json::Value>::new()).kind(),
+            ("any".to_string(), true)
+        );
+        assert_eq!(
+            FieldSchema::new("address", "option<object>")
+                .flexible()
+                .definition(),
+            "FLEXIBLE TYPE option<object>"
+        );
+    }
+
+    #[test]
+    fn test_relation_table() {
+        let schema = TableSchema::new("members")
+            .schemafull()
+            .relation("users", "projects")
+            .field(FieldSchema::new("role", "string"));
+        assert_eq!(
+            schema.statements(),
+            vec![
+                "DEFINE TABLE OVERWRITE members TYPE RELATION IN users OUT projects SCHEMAFULL",
+                "DEFINE FIELD OVERWRITE role ON TABLE members TYPE string",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_schemaless_table() {
+        assert_eq!(
+            TableSchema::new("logs").statements(),
+            vec!["DEFINE TABLE OVERWRITE logs SCHEMALESS"]
+        );
+    }
+}
@@ -0,0 +1,488 @@
+//! Seed data loaded from fixture files
+//!
+//! Fixtures map table names to records keyed by a symbolic name. A string
+//! value `"@name"` anywhere in a record is replaced by the id of the record
+//! called `name`, which is created first (`"@@"` escapes a leading `@`):
+//!
+//! ```yaml
+//! users:
+//!   alice:
+//!     username: alice
+//!     email: alice@example.com
+//! projects:
+//!   website:
+//!     name: Website
+//!     owner: "@alice"
+//! ```
+//!
+//! ```ignore
+//! let fixtures = Fixtures::from_path("fixtures/demo.yaml")?;
+//! let seeded = Seeder::new()
+//!     .register_factory::<User>()
+//!     .register::<Project>()
+//!     .load(&db, &fixtures)
+//!     .await?;
+//! let alice: Id<User> = seeded.id("alice").unwrap();
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::{error::Error as StdError, fmt};
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use surrealdb::RecordId;
+
+use crate::factory::Factory;
+use crate::hooks::ModelHooks;
+use crate::migration::BoxFuture;
+use crate::{Error, Id, Model, SurrealClient};
+
+type Loader = Box<
+    dyn for<'a> Fn(&'a SurrealClient, Value) -> BoxFuture<'a, crate::Result<Option<RecordId>>>
+        + Send
+        + Sync,
+>;
+
+#[derive(Debug)]
+pub enum SeedError {
+    Io(std::io::Error),
+    /// The fixtures are not a map of tables to named records
+    Parse(String),
+    /// Fixtures of a table which has no registered model
+    UnknownTable(String),
+    /// Two records share the same symbolic name
+    DuplicateName(String),
+    /// `@reference` does not name a record of the fixtures with an id
+    UnknownReference {
+        record: String,
+        reference: String,
+    },
+    /// Records which reference each other, directly or not
+    Cycle(Vec<String>),
+    /// Creating the record failed
+    Model {
+        record: String,
+        error: Error,
+    },
+}
+
+pub type SeedResult<T> = std::result::Result<T, SeedError>;
+
+impl fmt::Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedError::Io(err) => write!(f, "{}", err),
+            SeedError::Parse(message) => write!(f, "Invalid fixtures: {}", message),
+            SeedError::UnknownTable(table) => {
+                write!(f, "No model is registered for table `{}`", table)
+            }
+            SeedError::DuplicateName(name) => write!(f, "Duplicate record name `{}`", name),
+            SeedError::UnknownReference { record, reference } => {
+                write!(f, "Record `{}` references unknown `@{}`", record, reference)
+            }
+            SeedError::Cycle(records) => {
+                write!(f, "Records reference each other: {}", records.join(" -> "))
+            }
+            SeedError::Model { record, error } => {
+                write!(f, "Failed to create `{}`: {}", record, error)
+            }
+        }
+    }
+}
+
+impl StdError for SeedError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            SeedError::Io(err) => Some(err),
+            SeedError::Model { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SeedError {
+    fn from(err: std::io::Error) -> Self {
+        SeedError::Io(err)
+    }
+}
+
+/// Records to seed, by table and symbolic name
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    tables: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+impl Fixtures {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_json(json: &str) -> SeedResult<Self> {
+        let value = serde_json::from_str(json).map_err(|err| SeedError::Parse(err.to_string()))?;
+        Self::from_value(value)
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> SeedResult<Self> {
+        let value = serde_yaml::from_str(yaml).map_err(|err| SeedError::Parse(err.to_string()))?;
+        Self::from_value(value)
+    }
+
+    /// Read a `.json` file, or a `.yaml`/`.yml` file with the `yaml` feature
+    pub fn from_path(path: impl AsRef<Path>) -> SeedResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Self::from_json(&content),
+            #[cfg(feature = "yaml")]
+            Some("yaml" | "yml") => Self::from_yaml(&content),
+            _ => Err(SeedError::Parse(format!(
+                "Unsupported fixture file `{}`",
+                path.display()
+            ))),
+        }
+    }
+
+    pub fn from_value(value: Value) -> SeedResult<Self> {
+        let Value::Object(tables) = value else {
+            return Err(SeedError::Parse("expected a map of tables".to_string()));
+        };
+        let mut fixtures = Fixtures::new();
+        for (table, records) in tables {
+            let Value::Object(records) = records else {
+                return Err(SeedError::Parse(format!(
+                    "expected a map of records for `{}`",
+                    table
+                )));
+            };
+            for (name, record) in records {
+                fixtures = fixtures.record(&table, &name, record)?;
+            }
+        }
+        Ok(fixtures)
+    }
+
+    /// Add a record, names are unique across all tables
+    pub fn record(mut self, table: &str, name: &str, value: Value) -> SeedResult<Self> {
+        if !value.is_object() {
+            return Err(SeedError::Parse(format!(
+                "expected the fields of `{}` to be a map",
+                name
+            )));
+        }
+        if self.table_of(name).is_some() {
+            return Err(SeedError::DuplicateName(name.to_string()));
+        }
+        self.tables
+            .entry(table.to_string())
+            .or_default()
+            .insert(name.to_string(), value);
+        Ok(self)
+    }
+
+    /// Add the records of `other`, e.g. of another fixture file
+    pub fn merge(mut self, other: Fixtures) -> SeedResult<Self> {
+        for (table, records) in other.tables {
+            for (name, record) in records {
+                self = self.record(&table, &name, record)?;
+            }
+        }
+        Ok(self)
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.values().map(BTreeMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn table_of(&self, name: &str) -> Option<&str> {
+        self.tables
+            .iter()
+            .find(|(_, records)| records.contains_key(name))
+            .map(|(table, _)| table.as_str())
+    }
+
+    fn get(&self, name: &str) -> Option<&Value> {
+        self.tables.values().find_map(|records| records.get(name))
+    }
+
+    /// Names of all records, each after the records it references
+    fn ordered(&self) -> SeedResult<Vec<&str>> {
+        fn visit<'a>(
+            fixtures: &'a Fixtures,
+            name: &'a str,
+            path: &mut Vec<&'a str>,
+            ordered: &mut Vec<&'a str>,
+        ) -> SeedResult<()> {
+            if ordered.contains(&name) {
+                return Ok(());
+            }
+            if let Some(start) = path.iter().position(|visiting| *visiting == name) {
+                let mut cycle = path[start..]
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                cycle.push(name.to_string());
+                return Err(SeedError::Cycle(cycle));
+            }
+            let value = fixtures.get(name).expect("record names are checked");
+            path.push(name);
+            let mut references = vec![];
+            collect_references(value, &mut references);
+            for reference in references {
+                if fixtures.get(reference).is_none() {
+                    return Err(SeedError::UnknownReference {
+                        record: name.to_string(),
+                        reference: reference.to_string(),
+                    });
+                }
+                visit(fixtures, reference, path, ordered)?;
+            }
+            path.pop();
+            ordered.push(name);
+            Ok(())
+        }
+
+        let mut ordered = vec![];
+        for records in self.tables.values() {
+            for name in records.keys() {
+                visit(self, name, &mut vec![], &mut ordered)?;
+            }
+        }
+        Ok(ordered)
+    }
+}
+
+/// Name referenced by a string value, `None` for other values
+fn reference(value: &str) -> Option<&str> {
+    value
+        .strip_prefix('@')
+        .filter(|name| !name.starts_with('@'))
+}
+
+fn collect_references<'a>(value: &'a Value, references: &mut Vec<&'a str>) {
+    match value {
+        Value::String(value) => references.extend(reference(value)),
+        Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_references(value, references)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|value| collect_references(value, references)),
+        _ => {}
+    }
+}
+
+/// Replace references by the ids of the created records and unescape `@@`
+fn resolve(record: &str, value: Value, ids: &BTreeMap<String, RecordId>) -> SeedResult<Value> {
+    Ok(match value {
+        Value::String(value) => match reference(&value) {
+            Some(name) => {
+                let id = ids.get(name).ok_or_else(|| SeedError::UnknownReference {
+                    record: record.to_string(),
+                    reference: name.to_string(),
+                })?;
+                serde_json::to_value(id).map_err(|err| SeedError::Parse(err.to_string()))?
+            }
+            None => match value.strip_prefix('@') {
+                Some(escaped) => Value::String(escaped.to_string()),
+                None => Value::String(value),
+            },
+        },
+        Value::Array(values) => Value::Array(
+            values
+                .into_iter()
+                .map(|value| resolve(record, value, ids))
+                .collect::<SeedResult<_>>()?,
+        ),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(field, value)| Ok((field, resolve(record, value, ids)?)))
+                .collect::<SeedResult<_>>()?,
+        ),
+        value => value,
+    })
+}
+
+/// Creates the records of [`Fixtures`] through [`Objects`](crate::Objects)
+///
+/// Records are created one by one in dependency order, running hooks and
+/// validation. A failure leaves the records created before it in place.
+#[derive(Default)]
+pub struct Seeder {
+    loaders: HashMap<&'static str, Loader>,
+}
+
+impl Seeder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seed the table of `T`, fixtures hold every field of its input
+    pub fn register<T>(mut self) -> Self
+    where
+        T: ModelHooks + Send + Sync + 'static,
+        T::Input: DeserializeOwned + Send + Sync,
+    {
+        self.loaders.insert(
+            T::TABLE_NAME,
+            Box::new(|client, value| Box::pin(create::<T>(client, value))),
+        );
+        self
+    }
+
+    /// Seed the table of `T`, fields missing from the fixtures are built by its [`Factory`]
+    pub fn register_factory<T>(mut self) -> Self
+    where
+        T: Factory + Send + Sync + 'static,
+        T::Input: DeserializeOwned + Send + Sync,
+    {
+        self.loaders.insert(
+            T::TABLE_NAME,
+            Box::new(|client, value| {
+                Box::pin(async move {
+                    let mut input = serde_json::to_value(T::factory().build())
+                        .map_err(|err| Error::Validation(err.to_string()))?;
+                    if let (Some(input), Value::Object(fields)) = (input.as_object_mut(), value) {
+                        input.extend(fields);
+                    }
+                    create::<T>(client, input).await
+                })
+            }),
+        );
+        self
+    }
+
+    /// Create all records of `fixtures`
+    pub async fn load(&self, client: &SurrealClient, fixtures: &Fixtures) -> SeedResult<Seeded> {
+        if let Some(table) = fixtures
+            .tables
+            .keys()
+            .find(|table| !self.loaders.contains_key(table.as_str()))
+        {
+            return Err(SeedError::UnknownTable(table.clone()));
+        }
+
+        let mut records = BTreeMap::new();
+        for name in fixtures.ordered()? {
+            let table = fixtures.table_of(name).expect("record names are checked");
+            let value = resolve(name, fixtures.get(name).cloned().unwrap(), &records)?;
+            let id =
+                self.loaders[table](client, value)
+                    .await
+                    .map_err(|error| SeedError::Model {
+                        record: name.to_string(),
+                        error,
+                    })?;
+            if let Some(id) = id {
+                records.insert(name.to_string(), id);
+            }
+        }
+        Ok(Seeded { records })
+    }
+}
+
+async fn create<T>(client: &SurrealClient, value: Value) -> crate::Result<Option<RecordId>>
+where
+    T: ModelHooks,
+    T::Input: DeserializeOwned,
+{
+    let input: T::Input =
+        serde_json::from_value(value).map_err(|err| Error::Validation(err.to_string()))?;
+    let created = T::objects(client)
+        .create(input)
+        .await?
+        .ok_or(Error::NotFound)?;
+    Ok(created.record_id())
+}
+
+/// Ids of the records created by [`Seeder::load`], by symbolic name
+#[derive(Debug, Clone, Default)]
+pub struct Seeded {
+    records: BTreeMap<String, RecordId>,
+}
+
+impl Seeded {
+    pub fn get(&self, name: &str) -> Option<&RecordId> {
+        self.records.get(name)
+    }
+
+    /// Id of the record called `name`, `None` if it is not a record of `T`
+    pub fn id<T: Model>(&self, name: &str) -> Option<Id<T>> {
+        Id::try_from(self.get(name)?.clone()).ok()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &RecordId)> {
+        self.records.iter().map(|(name, id)| (name.as_str(), id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_order() {
+        let fixtures = Fixtures::from_json(
+            r#"{
+                "projects": { "website": { "owner": "@bob", "members": ["@alice", "@bob"] } },
+                "users": { "alice": { "name": "alice" }, "bob": { "name": "@@bob", "manager": "@alice" } }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(fixtures.len(), 3);
+        assert_eq!(fixtures.ordered().unwrap(), vec!["alice", "bob", "website"]);
+
+        let ids = BTreeMap::from([
+            ("alice".to_string(), RecordId::from(("users", "a"))),
+            ("bob".to_string(), RecordId::from(("users", "b"))),
+        ]);
+        let bob = resolve("bob", fixtures.get("bob").cloned().unwrap(), &ids).unwrap();
+        assert_eq!(bob["name"], json!("@bob"));
+        assert_eq!(
+            serde_json::from_value::<RecordId>(bob["manager"].clone()).unwrap(),
+            RecordId::from(("users", "a"))
+        );
+    }
+
+    #[test]
+    fn test_invalid_fixtures() {
+        let cycle =
+            Fixtures::from_json(r#"{ "users": { "a": { "x": "@b" }, "b": { "x": "@a" } } }"#)
+                .unwrap();
+        assert!(matches!(
+            cycle.ordered(),
+            Err(SeedError::Cycle(records)) if records == ["a", "b", "a"]
+        ));
+
+        let unknown = Fixtures::from_json(r#"{ "users": { "a": { "x": "@c" } } }"#).unwrap();
+        assert!(matches!(
+            unknown.ordered(),
+            Err(SeedError::UnknownReference { record, reference }) if record == "a" && reference == "c"
+        ));
+
+        assert!(matches!(
+            Fixtures::from_json(r#"{ "users": { "a": {} }, "projects": { "a": {} } }"#),
+            Err(SeedError::DuplicateName(name)) if name == "a"
+        ));
+        assert!(matches!(
+            Fixtures::from_json(r#"{ "users": ["a"] }"#),
+            Err(SeedError::Parse(_))
+        ));
+    }
+}
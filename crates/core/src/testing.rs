@@ -0,0 +1,283 @@
+//! Helpers for integration tests against an in-memory database
+//!
+//! ```ignore
+//! let db = TestDb::with_schema(Schema::new().register::<User>()).await?;
+//! let user: User = db.fixture(UserInput { .. }).await?;
+//!
+//! let client = TestClient::new(Router::new().nest("/auth", routes(db.client())));
+//! let response = client.post("/auth/login").json(&body).send().await;
+//! assert_eq!(response.status(), StatusCode::OK);
+//! ```
+
+use std::ops::Deref;
+
+use axum::Router;
+use axum::body::{Body, Bytes};
+use axum::http::{HeaderMap, HeaderName, HeaderValue, Method, Request, StatusCode, header};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tower::ServiceExt;
+
+use crate::hooks::ModelHooks;
+use crate::schema::Schema;
+use crate::{Error, Result, SurrealClient};
+
+/// Database used by a single test
+///
+/// Every instance runs its own `mem://` datastore, so tests can run in
+/// parallel without sharing records. Derefs to the [`SurrealClient`] so it
+/// can be passed wherever a client is expected.
+pub struct TestDb {
+    client: SurrealClient,
+}
+
+impl TestDb {
+    pub async fn new() -> Result<Self> {
+        let client = surrealdb::engine::any::connect("mem://").await?;
+        client.use_ns("test").use_db("test").await?;
+        Ok(TestDb { client })
+    }
+
+    /// Create a database with the tables of `schema` defined
+    pub async fn with_schema(schema: Schema) -> Result<Self> {
+        let db = Self::new().await?;
+        db.apply(&schema).await?;
+        Ok(db)
+    }
+
+    pub async fn apply(&self, schema: &Schema) -> Result<()> {
+        Ok(schema.apply(&self.client).await?)
+    }
+
+    pub fn client(&self) -> &SurrealClient {
+        &self.client
+    }
+
+    /// Create a record through [`Objects::create`](crate::Objects::create), running its hooks
+    pub async fn fixture<T>(&self, input: T::Input) -> Result<T>
+    where
+        T: ModelHooks,
+    {
+        T::objects(&self.client)
+            .create(input)
+            .await?
+            .ok_or(Error::NotFound)
+    }
+
+    /// Create a record with the given key, see [`TestDb::fixture`]
+    pub async fn fixture_with_id<T>(&self, id: impl Into<String>, input: T::Input) -> Result<T>
+    where
+        T: ModelHooks,
+    {
+        T::objects(&self.client)
+            .create_with_id(id.into(), input)
+            .await?
+            .ok_or(Error::NotFound)
+    }
+}
+
+impl Deref for TestDb {
+    type Target = SurrealClient;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
+
+/// Sends requests to an axum [`Router`] without binding a socket
+#[derive(Clone)]
+pub struct TestClient {
+    router: Router,
+}
+
+impl TestClient {
+    pub fn new(router: Router) -> Self {
+        TestClient { router }
+    }
+
+    pub fn get(&self, path: &str) -> TestRequest {
+        self.request(Method::GET, path)
+    }
+
+    pub fn post(&self, path: &str) -> TestRequest {
+        self.request(Method::POST, path)
+    }
+
+    pub fn put(&self, path: &str) -> TestRequest {
+        self.request(Method::PUT, path)
+    }
+
+    pub fn patch(&self, path: &str) -> TestRequest {
+        self.request(Method::PATCH, path)
+    }
+
+    pub fn delete(&self, path: &str) -> TestRequest {
+        self.request(Method::DELETE, path)
+    }
+
+    pub fn request(&self, method: Method, path: &str) -> TestRequest {
+        TestRequest {
+            router: self.router.clone(),
+            method,
+            path: path.to_string(),
+            headers: HeaderMap::new(),
+            body: Bytes::new(),
+        }
+    }
+}
+
+/// Request built by [`TestClient`], sent with [`TestRequest::send`]
+pub struct TestRequest {
+    router: Router,
+    method: Method,
+    path: String,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl TestRequest {
+    /// Set a header, panics if it is not a valid header
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        let name = HeaderName::try_from(name).expect("invalid header name");
+        let value = HeaderValue::try_from(value).expect("invalid header value");
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Set the `Authorization: Bearer <token>` header
+    pub fn bearer(self, token: &str) -> Self {
+        self.header(header::AUTHORIZATION.as_str(), &format!("Bearer {}", token))
+    }
+
+    /// Send `body` as JSON
+    pub fn json(mut self, body: &impl Serialize) -> Self {
+        self.body = serde_json::to_vec(body)
+            .expect("request body must serialize to JSON")
+            .into();
+        self.headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("application/json"),
+        );
+        self
+    }
+
+    pub async fn send(self) -> TestResponse {
+        let mut request = Request::new(Body::from(self.body));
+        *request.method_mut() = self.method;
+        *request.uri_mut() = self.path.parse().expect("invalid request path");
+        *request.headers_mut() = self.headers;
+
+        let response = match self.router.oneshot(request).await {
+            Ok(response) => response,
+            Err(infallible) => match infallible {},
+        };
+        let (parts, body) = response.into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX)
+            .await
+            .expect("failed to read response body");
+        TestResponse {
+            status: parts.status,
+            headers: parts.headers,
+            body,
+        }
+    }
+}
+
+/// Response collected by [`TestRequest::send`]
+#[derive(Debug)]
+pub struct TestResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl TestResponse {
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn bytes(&self) -> &Bytes {
+        &self.body
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Deserialize the body, panics with the body if it does not match `T`
+    pub fn json<T: DeserializeOwned>(&self) -> T {
+        serde_json::from_slice(&self.body).unwrap_or_else(|err| {
+            panic!("invalid JSON response ({}): {}", err, self.text());
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::Json;
+    use axum::routing::post;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Note {
+        id: surrealdb::RecordId,
+        text: String,
+    }
+
+    #[tokio::test]
+    async fn test_isolated_databases() {
+        let first = TestDb::new().await.unwrap();
+        let second = TestDb::new().await.unwrap();
+
+        let _: Option<Note> = first
+            .create("notes")
+            .content(serde_json::json!({ "text": "first" }))
+            .await
+            .unwrap();
+        let first_notes: Vec<Note> = first.select("notes").await.unwrap();
+        let second_notes: Vec<Note> = second.select("notes").await.unwrap();
+        assert_eq!(first_notes.len(), 1);
+        assert_eq!(first_notes[0].text, "first");
+        assert!(second_notes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_client() {
+        let router = Router::new().route(
+            "/echo",
+            post(
+                async |headers: HeaderMap, Json(body): Json<serde_json::Value>| {
+                    let auth = headers
+                        .get(header::AUTHORIZATION)
+                        .and_then(|value| value.to_str().ok())
+                        .unwrap_or_default()
+                        .to_string();
+                    Json(serde_json::json!({ "auth": auth, "body": body }))
+                },
+            ),
+        );
+        let client = TestClient::new(router);
+
+        let response = client
+            .post("/echo")
+            .bearer("token")
+            .json(&serde_json::json!({ "a": 1 }))
+            .send()
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.json::<serde_json::Value>(),
+            serde_json::json!({ "auth": "Bearer token", "body": { "a": 1 } })
+        );
+        assert_eq!(
+            client.get("/missing").send().await.status(),
+            StatusCode::NOT_FOUND
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use surrealdb::RecordId;
+use surrealdb::opt::QueryResult;
+
+use crate::error::{GUARD_PREFIX, VERSION_CONFLICT};
+use crate::hooks::NoHooks;
+use crate::query::{Condition, Param, Params, escape_field, render_delete_record};
+use crate::{IntoRecordId, Model, Result, SurrealClient, Validate};
+
+/// Handle to the result of a statement queued in a [`Transaction`]
+#[must_use]
+pub struct Pending<T> {
+    index: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for Pending<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Pending").field(&self.index).finish()
+    }
+}
+
+/// A batch of writes executed atomically in a single
+/// `BEGIN TRANSACTION ... COMMIT TRANSACTION` request
+///
+/// SurrealDB does not keep transactions open across requests, so statements
+/// are queued and only sent on [`commit`](Transaction::commit). If any
+/// statement fails, nothing is written. Results are read back from the
+/// returned [`Committed`] using the [`Pending`] handles.
+///
+/// ```ignore
+/// let mut tx = Transaction::new(db);
+/// let user = tx.objects::<User>().create_with_id(key, user_input);
+/// let _ = tx.objects::<AuthSession>().create_with_id(session_id, session_input);
+/// let user = tx.commit().await?.take(user)?;
+/// ```
+///
+/// Writes are only available for models without
+/// [`ModelHooks`](crate::ModelHooks), see [`NoHooks`].
+pub struct Transaction<'c> {
+    client: &'c SurrealClient,
+    statements: Vec<String>,
+    params: Params,
+    error: Option<crate::Error>,
+}
+
+impl<'c> Transaction<'c> {
+    pub fn new(client: &'c SurrealClient) -> Self {
+        Transaction {
+            client,
+            statements: vec![],
+            params: Params::default(),
+            error: None,
+        }
+    }
+
+    /// Queue writes on the table of model `T`
+    pub fn objects<T: NoHooks>(&mut self) -> TransactionObjects<'_, 'c, T> {
+        TransactionObjects {
+            tx: self,
+            _model: PhantomData,
+        }
+    }
+
+    pub(crate) fn param<V: Serialize + 'static>(&mut self, value: V) -> String {
+        self.params.push(&Param::new(value))
+    }
+
+    /// Validate `data`, the first failure is returned by [`commit`](Transaction::commit)
+    pub(crate) fn validate<V: Validate>(&mut self, data: &V) {
+        if let Err(errors) = data.validate() {
+            self.error.get_or_insert(errors.into());
+        }
+    }
+
+    pub(crate) fn push<T>(&mut self, statement: String) -> Pending<T> {
+        self.statements.push(statement);
+        Pending {
+            index: self.statements.len() - 1,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Abort the transaction with [`Error::Conflict`](crate::Error::Conflict) unless a record
+    /// of `T` matches `condition` when the transaction runs
+    pub fn ensure<T: Model>(&mut self, condition: Condition, message: &str) {
+        self.guard::<T>(condition, "==", message);
+    }
+
+    /// Abort the transaction with [`Error::Conflict`](crate::Error::Conflict) if a record of
+    /// `T` matches `condition` when the transaction runs
+    pub fn ensure_none<T: Model>(&mut self, condition: Condition, message: &str) {
+        self.guard::<T>(condition, ">", message);
+    }
+
+    fn guard<T: Model>(&mut self, condition: Condition, cmp: &str, message: &str) {
+        let table = self.param(T::TABLE_NAME);
+        let condition = condition.render(&mut self.params);
+        let message = self.param(format!("{}{}", GUARD_PREFIX, message));
+        let _ = self.push::<()>(format!(
+            "IF array::len(SELECT id FROM type::table({}) WHERE {} LIMIT 1) {} 0 {{ THROW {} }}",
+            table, condition, cmp, message
+        ));
+    }
+
+    /// Abort the transaction with [`Error::Conflict`](crate::Error::Conflict) if `record`
+    /// exists with a different version than the one in `data`
+    fn version_guard<T: Model>(&mut self, record: &str, data: &str) {
+        let Some(field) = T::VERSION_FIELD else {
+            return;
+        };
+        let field = escape_field(field);
+        let message = self.param(format!("{}{}", GUARD_PREFIX, VERSION_CONFLICT));
+        let _ = self.push::<()>(format!(
+            "IF array::len(SELECT id FROM {record} WHERE {data}.{field} != NONE AND {field} != {data}.{field}) > 0 {{ THROW {message} }}",
+        ));
+    }
+
+    /// Replace the record `id` with `model`, as done by the derived `save`
+    pub fn save<T>(&mut self, id: impl Into<RecordId>, model: T) -> Pending<Option<T>>
+    where
+        T: NoHooks + Validate + 'static,
+    {
+        self.validate(&model);
+        let id = self.param(id.into());
+        let content = self.param(model);
+        self.version_guard::<T>(&id, &content);
+        self.push(format!("UPDATE {} CONTENT {}", id, content))
+    }
+
+    /// Update only the fields set in `patch` on the record `id`
+    pub fn merge<T: NoHooks>(
+        &mut self,
+        id: impl Into<RecordId>,
+        patch: T::Patch,
+    ) -> Pending<Option<T>> {
+        self.validate(&patch);
+        let id = self.param(id.into());
+        let patch = self.param(patch);
+        self.version_guard::<T>(&id, &patch);
+        self.push(format!("UPDATE {} MERGE {}", id, patch))
+    }
+
+    /// Delete the record `id`, as done by the derived `delete`
+    pub fn delete<T: NoHooks>(&mut self, id: impl Into<RecordId>) -> Pending<Option<T>> {
+        let id = self.param(id.into());
+        self.push(render_delete_record::<T>(&id))
+    }
+
+    /// Send all queued statements in one transaction
+    pub async fn commit(self) -> Result<Committed> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        let sql = format!(
+            "BEGIN TRANSACTION;\n{};\nCOMMIT TRANSACTION;",
+            self.statements.join(";\n")
+        );
+        let mut response = self
+            .client
+            .query(sql)
+            .bind(self.params.into_bindings()?)
+            .await?;
+        let mut errors = response.take_errors().into_iter().collect::<Vec<_>>();
+        errors.sort_by_key(|(index, _)| *index);
+        // Statements after the failing one report that they were not executed,
+        // the interesting error is the first one that has a different cause
+        let cause = errors
+            .iter()
+            .position(|(_, err)| !is_not_executed(err))
+            .unwrap_or(0);
+        match errors.into_iter().nth(cause) {
+            Some((_, err)) => Err(err.into()),
+            None => Ok(Committed { response }),
+        }
+    }
+}
+
+fn is_not_executed(err: &surrealdb::Error) -> bool {
+    matches!(
+        err,
+        surrealdb::Error::Db(
+            surrealdb::error::Db::QueryNotExecuted
+                | surrealdb::error::Db::QueryNotExecutedDetail { .. }
+        )
+    ) || err
+        .to_string()
+        .contains("not executed due to a failed transaction")
+}
+
+/// Results of a committed [`Transaction`]
+pub struct Committed {
+    response: surrealdb::Response,
+}
+
+impl Committed {
+    pub fn take<T>(&mut self, pending: Pending<T>) -> Result<T>
+    where
+        T: DeserializeOwned,
+        usize: QueryResult<T>,
+    {
+        Ok(self.response.take(pending.index)?)
+    }
+}
+
+/// Writes on the table of model `T` queued in a [`Transaction`]
+///
+/// Mirrors the write operations of [`Objects`](crate::Objects).
+pub struct TransactionObjects<'t, 'c, T> {
+    tx: &'t mut Transaction<'c>,
+    _model: PhantomData<T>,
+}
+
+impl<T> TransactionObjects<'_, '_, T>
+where
+    T: NoHooks,
+{
+    fn thing(&mut self, id: impl IntoRecordId<T>) -> String {
+        self.tx.param(id.into_record_id())
+    }
+
+    pub fn create(&mut self, data: T::Input) -> Pending<Option<T>> {
+        self.tx.validate(&data);
+        let table = self.tx.param(T::TABLE_NAME);
+        let content = self.tx.param(data);
+        self.tx
+            .push(format!("CREATE type::table({}) CONTENT {}", table, content))
+    }
+
+    pub fn create_with_id(&mut self, id: String, data: T::Input) -> Pending<Option<T>> {
+        self.tx.validate(&data);
+        let thing = self.thing(id);
+        let content = self.tx.param(data);
+        self.tx
+            .push(format!("CREATE {} CONTENT {}", thing, content))
+    }
+
+    pub fn update(&mut self, id: impl IntoRecordId<T>, data: T::Input) -> Pending<Option<T>> {
+        self.tx.validate(&data);
+        let thing = self.thing(id);
+        let content = self.tx.param(data);
+        self.tx.version_guard::<T>(&thing, &content);
+        self.tx
+            .push(format!("UPDATE {} CONTENT {}", thing, content))
+    }
+
+    pub fn merge(&mut self, id: impl IntoRecordId<T>, patch: T::Patch) -> Pending<Option<T>> {
+        self.tx.validate(&patch);
+        let thing = self.thing(id);
+        let patch = self.tx.param(patch);
+        self.tx.version_guard::<T>(&thing, &patch);
+        self.tx.push(format!("UPDATE {} MERGE {}", thing, patch))
+    }
+
+    pub fn upsert(&mut self, id: impl IntoRecordId<T>, data: T::Input) -> Pending<Option<T>> {
+        self.tx.validate(&data);
+        let thing = self.thing(id);
+        let content = self.tx.param(data);
+        self.tx
+            .push(format!("UPSERT {} CONTENT {}", thing, content))
+    }
+
+    pub fn delete(&mut self, id: impl IntoRecordId<T>) -> Pending<Option<T>> {
+        let thing = self.thing(id);
+        self.tx.push(render_delete_record::<T>(&thing))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::hooks::ModelHooks;
+
+    #[derive(Serialize, Deserialize)]
+    struct AnyModel {}
+
+    impl Model for AnyModel {
+        const TABLE_NAME: &'static str = "any_model";
+        #[cfg(feature = "utoipa")]
+        type Data = ();
+        type Input = ();
+        type Patch = ();
+        fn table_name(&self) -> &'static str {
+            Self::TABLE_NAME
+        }
+        #[cfg(feature = "utoipa")]
+        fn into_data(self) -> Self::Data {}
+    }
+
+    impl ModelHooks for AnyModel {}
+    impl NoHooks for AnyModel {}
+
+    #[test]
+    fn test_statements() {
+        let client = surrealdb::Surreal::init();
+        let mut tx = Transaction::new(&client);
+        tx.ensure_none::<AnyModel>(Condition::eq("name", "merak"), "name");
+        let created = tx
+            .objects::<AnyModel>()
+            .create_with_id("merak".to_string(), ());
+        let deleted = tx.objects::<AnyModel>().delete("old");
+        assert_eq!(created.index, 1);
+        assert_eq!(deleted.index, 2);
+        assert_eq!(
+            tx.statements,
+            vec![
+                "IF array::len(SELECT id FROM type::table($p0) WHERE name = $p1 LIMIT 1) > 0 { THROW $p2 }",
+                "CREATE $p3 CONTENT $p4",
+                "DELETE $p5 RETURN BEFORE",
+            ]
+        );
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct VersionedModel {}
+
+    impl Model for VersionedModel {
+        const TABLE_NAME: &'static str = "versioned_model";
+        #[cfg(feature = "utoipa")]
+        type Data = ();
+        type Input = ();
+        type Patch = ();
+        const VERSION_FIELD: Option<&'static str> = Some("version");
+        fn table_name(&self) -> &'static str {
+            Self::TABLE_NAME
+        }
+        #[cfg(feature = "utoipa")]
+        fn into_data(self) -> Self::Data {}
+    }
+
+    impl ModelHooks for VersionedModel {}
+    impl NoHooks for VersionedModel {}
+
+    #[test]
+    fn test_version_guard() {
+        let client = surrealdb::Surreal::init();
+        let mut tx = Transaction::new(&client);
+        let updated = tx.objects::<VersionedModel>().update("merak", ());
+        assert_eq!(updated.index, 1);
+        assert_eq!(
+            tx.statements,
+            vec![
+                "IF array::len(SELECT id FROM $p0 WHERE $p1.version != NONE AND version != $p1.version) > 0 { THROW $p2 }",
+                "UPDATE $p0 CONTENT $p1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_not_executed() {
+        let err: surrealdb::Error = surrealdb::error::Db::QueryNotExecuted.into();
+        assert!(is_not_executed(&err));
+        let err: surrealdb::Error = surrealdb::error::Db::Thrown("boom".to_string()).into();
+        assert!(!is_not_executed(&err));
+    }
+}
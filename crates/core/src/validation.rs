@@ -0,0 +1,185 @@
+use std::fmt;
+
+use serde::Serialize;
+
+pub use regex::Regex;
+
+/// Values checked by `#[field(validate(...))]` rules, generated by the `Model` derive
+///
+/// Implemented for the model and its `Input` and `Patch` structs. Writes
+/// through [`Objects`](crate::Objects) and
+/// [`TransactionObjects`](crate::transaction::TransactionObjects) validate
+/// their data and fail with [`Error::Invalid`](crate::Error::Invalid).
+pub trait Validate {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        Ok(())
+    }
+}
+
+impl Validate for () {}
+
+/// A rule violated by the value of a field
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    pub field: &'static str,
+    /// Name of the violated rule, like `length` or `email`
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// All rules violated by a value, in field order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct ValidationErrors(Vec<FieldError>);
+
+impl ValidationErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the message returned by a rule, if any
+    pub fn check(&mut self, field: &'static str, code: &'static str, result: Option<String>) {
+        if let Some(message) = result {
+            self.0.push(FieldError {
+                field,
+                code,
+                message,
+            });
+        }
+    }
+
+    pub fn errors(&self) -> &[FieldError] {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn into_result(self) -> Result<(), ValidationErrors> {
+        if self.is_empty() { Ok(()) } else { Err(self) }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors = self
+            .0
+            .iter()
+            .map(|error| format!("{}: {}", error.field, error.message))
+            .collect::<Vec<_>>();
+        write!(f, "{}", errors.join(", "))
+    }
+}
+
+/// Values with a length, counted in characters for strings
+pub trait HasLength {
+    fn length(&self) -> usize;
+}
+
+impl HasLength for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl HasLength for String {
+    fn length(&self) -> usize {
+        self.as_str().length()
+    }
+}
+
+impl<T> HasLength for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLength for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+pub fn length<V>(value: &V, min: Option<usize>, max: Option<usize>) -> Option<String>
+where
+    V: HasLength + ?Sized,
+{
+    let length = value.length();
+    match (min, max) {
+        (Some(min), Some(max)) if length < min || length > max => {
+            Some(format!("Length must be between {} and {}", min, max))
+        }
+        (Some(min), None) if length < min => Some(format!("Length must be at least {}", min)),
+        (None, Some(max)) if length > max => Some(format!("Length must be at most {}", max)),
+        _ => None,
+    }
+}
+
+pub fn range<V>(value: &V, min: Option<V>, max: Option<V>) -> Option<String>
+where
+    V: PartialOrd + fmt::Display,
+{
+    match (min, max) {
+        (Some(min), Some(max)) if *value < min || *value > max => {
+            Some(format!("Value must be between {} and {}", min, max))
+        }
+        (Some(min), None) if *value < min => Some(format!("Value must be at least {}", min)),
+        (None, Some(max)) if *value > max => Some(format!("Value must be at most {}", max)),
+        _ => None,
+    }
+}
+
+/// Check the shape `local@domain.tld`, deliverability is not checked
+pub fn email<V: AsRef<str> + ?Sized>(value: &V) -> Option<String> {
+    let value = value.as_ref();
+    let valid = match value.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.split('.').count() > 1
+                && domain.split('.').all(|part| !part.is_empty())
+                && !value.chars().any(char::is_whitespace)
+        }
+        None => false,
+    };
+    (!valid).then(|| "Must be a valid email address".to_string())
+}
+
+pub fn regex<V: AsRef<str> + ?Sized>(value: &V, regex: &Regex) -> Option<String> {
+    (!regex.is_match(value.as_ref())).then(|| format!("Must match `{}`", regex.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        assert_eq!(length("merak", Some(3), Some(50)), None);
+        assert!(length("me", Some(3), Some(50)).is_some());
+        assert!(length(&vec![1, 2, 3], None, Some(2)).is_some());
+        assert_eq!(range(&5, Some(1), Some(10)), None);
+        assert!(range(&0.5, Some(1.0), None).is_some());
+        assert_eq!(email("merak@example.com"), None);
+        assert!(email("merak@localhost").is_some());
+        assert!(email("merak example@x.com").is_some());
+        assert!(email("@example.com").is_some());
+        let pattern = Regex::new("^[a-z0-9_]+$").unwrap();
+        assert_eq!(regex("merak_1", &pattern), None);
+        assert!(regex("Merak!", &pattern).is_some());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut errors = ValidationErrors::new();
+        errors.check("username", "length", None);
+        assert!(errors.clone().into_result().is_ok());
+        errors.check("username", "length", length("me", Some(3), None));
+        errors.check("email", "email", email("merak"));
+        assert_eq!(
+            errors.to_string(),
+            "username: Length must be at least 3, email: Must be a valid email address"
+        );
+    }
+}
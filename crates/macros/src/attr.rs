@@ -1,9 +1,53 @@
-use darling::{FromDeriveInput, FromField};
+use darling::{FromDeriveInput, FromField, FromMeta};
+use syn::Token;
 
 #[derive(Default, FromDeriveInput)]
 #[darling(default, attributes(model))]
 pub struct ModelArgs {
     pub table_name: Option<String>,
+    pub relation: Option<RelationArgs>,
+    /// Mark records as deleted in a `deleted_at` field instead of removing them
+    pub soft_delete: bool,
+    /// Skip the no-op `ModelHooks` impl, the model provides its own
+    pub hooks: bool,
+    /// Path of `merak_core` when it is re-exported, `#[model(crate = "app::merak")]`
+    #[darling(rename = "crate")]
+    pub krate: Option<syn::Path>,
+}
+
+impl ModelArgs {
+    pub fn crate_path(&self) -> syn::Path {
+        crate_path(self.krate.as_ref())
+    }
+}
+
+/// `#[field_kind(crate = "...")]`, see [`ModelArgs::krate`]
+#[derive(Default, FromDeriveInput)]
+#[darling(default, attributes(field_kind))]
+pub struct FieldKindArgs {
+    #[darling(rename = "crate")]
+    pub krate: Option<syn::Path>,
+}
+
+impl FieldKindArgs {
+    pub fn crate_path(&self) -> syn::Path {
+        crate_path(self.krate.as_ref())
+    }
+}
+
+fn crate_path(krate: Option<&syn::Path>) -> syn::Path {
+    krate
+        .cloned()
+        .unwrap_or_else(|| syn::parse_quote!(::merak_core))
+}
+
+/// Endpoints of an edge model, `#[model(relation(in = User, out = Project))]`
+#[derive(FromMeta)]
+pub struct RelationArgs {
+    #[darling(rename = "in")]
+    pub from: syn::Ident,
+    #[darling(rename = "out")]
+    pub to: syn::Ident,
 }
 
 #[derive(Default, FromField)]
@@ -11,8 +55,22 @@ pub struct ModelArgs {
 pub struct FieldArgs {
     pub primary: bool,
     pub foreign_key: Option<syn::Ident>,
+    /// Name of the has-many accessor generated on the foreign model
+    pub related_name: Option<String>,
+    /// Set to the creation time by the generated schema, excluded from the input
     pub created_at: bool,
+    /// Set to the time of every write by the generated schema, excluded from the input
     pub updated_at: bool,
+    /// Incremented by the generated schema on every write and checked by `save`/`update`
+    pub version: bool,
+    pub unique: bool,
+    pub index: bool,
+    /// Leave the field out of the input and patch structs, it is set by the server
+    pub skip_input: bool,
+    /// Leave the field out of the API-facing data struct, e.g. for secrets
+    pub skip_data: bool,
+    /// Rules checked by the generated `Validate` impls
+    pub validate: Option<ValidateArgs>,
 }
 
 impl FieldArgs {
@@ -20,3 +78,77 @@ impl FieldArgs {
         self.foreign_key.is_some()
     }
 }
+
+/// Value of an input field built by the `Factory` derive, `Default::default()` when unset
+#[derive(Default, FromField)]
+#[darling(default, attributes(factory))]
+pub struct FactoryArgs {
+    pub default: Option<syn::Expr>,
+    /// Format string with the sequence number in scope as `n`, e.g. `"user{n}@example.com"`
+    pub sequence: Option<syn::LitStr>,
+}
+
+/// Rules of `#[field(validate(length(min = 3), email, regex = "...", range(max = 10), custom = path))]`
+#[derive(Default, FromMeta)]
+#[darling(default)]
+pub struct ValidateArgs {
+    pub length: Option<LengthArgs>,
+    pub email: bool,
+    pub regex: Option<String>,
+    pub range: Option<RangeArgs>,
+    /// Function `fn(&T) -> Result<(), String>` returning the error message
+    pub custom: Option<syn::Path>,
+}
+
+#[derive(Default, FromMeta)]
+#[darling(default)]
+pub struct LengthArgs {
+    pub min: Option<usize>,
+    pub max: Option<usize>,
+}
+
+#[derive(Default, FromMeta)]
+#[darling(default)]
+pub struct RangeArgs {
+    pub min: Option<syn::Expr>,
+    pub max: Option<syn::Expr>,
+}
+
+/// The `#[serde(...)]` options taken into account by the derives, others are ignored
+#[derive(Default)]
+pub struct SerdeArgs {
+    pub rename: Option<String>,
+    pub rename_all: Option<String>,
+    pub skip: bool,
+    pub default: bool,
+}
+
+impl SerdeArgs {
+    pub fn from_attrs(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut args = SerdeArgs::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                let has_value = meta.input.peek(Token![=]);
+                if meta.path.is_ident("rename") && has_value {
+                    args.rename = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                } else if meta.path.is_ident("rename_all") && has_value {
+                    args.rename_all = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                } else if meta.path.is_ident("skip") {
+                    args.skip = true;
+                } else if meta.path.is_ident("default") {
+                    args.default = true;
+                }
+                // Skip the value of `default = "path"` and of unknown options
+                if meta.input.peek(Token![=]) {
+                    meta.value()?.parse::<syn::Expr>()?;
+                } else if meta.input.peek(syn::token::Paren) {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    content.parse::<proc_macro2::TokenStream>()?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(args)
+    }
+}
@@ -1,97 +1,158 @@
-use darling::FromField;
+use darling::{FromDeriveInput, FromField};
+use heck::ToSnakeCase;
 use proc_macro2::{Span, TokenStream};
 use quote::quote;
 use syn::{Field, Ident, Visibility};
 
-use crate::{FieldArgs, is_record_id};
+use crate::{
+    attr::{
+        FactoryArgs, FieldArgs, FieldKindArgs, ModelArgs, RelationArgs, SerdeArgs, ValidateArgs,
+    },
+    model::ModelField,
+    utils::{
+        SurrealKind, field_name, generic_inner, is_helper_attr, is_record_id, is_relation_endpoint,
+        is_serde_skipped, linked_model, rename_case, static_kind, surreal_kind, type_name,
+    },
+};
 
-pub(crate) fn expand_foreign_methods<'a, F>(
-    fields: F,
+pub(crate) fn expand_foreign_methods(
+    krate: &syn::Path,
+    fields: &[ModelField],
     vis: &Visibility,
-) -> Result<Vec<TokenStream>, syn::Error>
-where
-    F: IntoIterator<Item = &'a Field>,
-{
-    fields.into_iter().try_fold(vec![], |mut acc, field| {
-        let field_ident = field.ident.as_ref().unwrap();
-        let method_ident = Ident::new(&field_ident.to_string().replace("_id", ""), Span::call_site());
-        let field_type = &field.ty;
-        let field_args = FieldArgs::from_field(field)?;
-        if is_record_id(field_type) && field_args.is_foreign_key() {
-            if field_ident == "id" {
-                return Err(syn::Error::new(Span::call_site(), "Foreign key field must NOT be named `id`"));
-            }
-            let foreign_key = field_args.foreign_key.unwrap();
-            acc.push(quote! {
-                #vis async fn #method_ident(&self, client: &::merak_core::SurrealClient) -> surrealdb::Result<Option<#foreign_key>> {
-                    client.select(&self.#field_ident).await
+) -> Vec<TokenStream> {
+    fields
+        .iter()
+        .filter_map(|field| {
+            let field_ident = field.ident;
+            let foreign_key = field.args.foreign_key.as_ref()?;
+            let method_ident = Ident::new(&field_ident.to_string().replace("_id", ""), Span::call_site());
+            Some(quote! {
+                #vis async fn #method_ident(&self, client: &#krate::SurrealClient) -> #krate::Result<::std::option::Option<#foreign_key>> {
+                    ::std::result::Result::Ok(client.select(#krate::surrealdb::RecordId::from(self.#field_ident.clone())).await?)
                 }
-            });
-        } else if field_args.is_foreign_key() {
-            return Err(syn::Error::new(Span::call_site(), "Foreign key field must be of type `RecordId`"));
-        }
-        Ok::<_, syn::Error>(acc)
-    })
+            })
+        })
+        .collect()
 }
 
-pub(crate) fn expand_input_struct<'a, F>(
-    fields: F,
+pub(crate) fn expand_input_struct(
+    fields: &[ModelField],
     vis: &Visibility,
     input_ident: &Ident,
-) -> Result<TokenStream, syn::Error>
-where
-    F: IntoIterator<Item = &'a Field>,
-{
+    model_args: &ModelArgs,
+) -> TokenStream {
     let input_fields = fields
-        .into_iter()
-        .filter(|field| {
-            let field_args = FieldArgs::from_field(field).unwrap();
-            !field_args.primary && !field_args.created_at && !field_args.updated_at
-        })
-        .map(|field| {
-            let mut field = field.clone();
-            field.attrs.retain(|attr| !attr.path().is_ident("field"));
+        .iter()
+        .filter(|field| is_input_field(field, model_args))
+        .map(|ModelField { field, args, .. }| {
+            let mut field = (*field).clone();
+            field.attrs.retain(|attr| !is_helper_attr(attr));
+            if args.version {
+                // Ignored on create, the schema starts every record at version 1,
+                // and updates without a version are not checked
+                let field_type = &field.ty;
+                field.ty = syn::parse_quote!(::std::option::Option<#field_type>);
+                if !SerdeArgs::from_attrs(&field.attrs).is_ok_and(|args| args.default) {
+                    field.attrs.push(syn::parse_quote!(#[serde(default)]));
+                }
+                field.attrs.push(
+                    syn::parse_quote!(#[serde(skip_serializing_if = "::std::option::Option::is_none")]),
+                );
+            }
             field
         });
-    Ok(quote! {
+    quote! {
         #[derive(::serde::Serialize, ::serde::Deserialize)]
         #vis struct #input_ident {
             #(#input_fields),*
         }
-    })
+    }
+}
+
+/// Whether the field is written by the user, as opposed to the primary key,
+/// timestamps and edge endpoints which are set by the database, fields set
+/// by the server and fields which are never stored
+pub(crate) fn is_input_field(field: &ModelField, model_args: &ModelArgs) -> bool {
+    let args = &field.args;
+    !args.primary
+        && !args.created_at
+        && !args.updated_at
+        && !args.skip_input
+        && !is_serde_skipped(field.field)
+        && !is_builtin_field(field.field, model_args)
+}
+
+/// Whether the field is defined by the table itself rather than by the struct,
+/// like the endpoints of an edge or `deleted_at` of a soft deleting model
+fn is_builtin_field(field: &Field, model_args: &ModelArgs) -> bool {
+    (model_args.relation.is_some() && is_relation_endpoint(field))
+        || (model_args.soft_delete && field_name(field) == "deleted_at")
+}
+
+pub(crate) fn expand_patch_struct(
+    fields: &[ModelField],
+    vis: &Visibility,
+    patch_ident: &Ident,
+    model_args: &ModelArgs,
+) -> TokenStream {
+    let patch_fields = fields
+        .iter()
+        .filter(|field| is_input_field(field, model_args))
+        .map(|ModelField { field, .. }| {
+            let mut field = (*field).clone();
+            let field_type = &field.ty;
+            field.ty = syn::parse_quote!(::std::option::Option<#field_type>);
+            field.attrs.retain(|attr| !is_helper_attr(attr));
+            field.attrs.push(
+                syn::parse_quote!(#[serde(skip_serializing_if = "::std::option::Option::is_none")]),
+            );
+            field
+        });
+    quote! {
+        #[derive(::std::default::Default, ::serde::Serialize, ::serde::Deserialize)]
+        #vis struct #patch_ident {
+            #(#patch_fields),*
+        }
+    }
 }
 
-pub(crate) fn expand_data_impl<'a, F>(
-    fields: F,
+pub(crate) fn expand_data_impl(
+    fields: &[ModelField],
     vis: &Visibility,
     ident: &Ident,
     data_ident: &Ident,
-) -> Result<TokenStream, syn::Error>
-where
-    F: IntoIterator<Item = &'a Field> + Clone,
-{
-    let data_fields = fields.clone().into_iter().map(|field| {
-        let mut field = field.clone();
-        if is_record_id(&field.ty) {
-            field.ty = syn::parse_quote!(String);
-        }
-        field.attrs.retain(|attr| !attr.path().is_ident("field"));
+) -> TokenStream {
+    let fields = fields
+        .iter()
+        .filter(|field| !field.args.skip_data && !is_serde_skipped(field.field))
+        .collect::<Vec<_>>();
+    let data_fields = fields.iter().map(|ModelField { field, args, .. }| {
+        let mut field = (*field).clone();
+        if let Some((ty, _)) = data_conversion(&field.ty, quote!(value)) {
+            field.ty = ty;
+        }
+        field.attrs.retain(|attr| !is_helper_attr(attr));
+        if let Some(schema) = args
+            .validate
+            .as_ref()
+            .and_then(|rules| validate_schema_attr(&field.ty, rules))
+        {
+            field.attrs.push(schema);
+        }
         field
     });
-    let covert_data_fields = fields.into_iter().map(|field| {
-        let field_ident = field.ident.as_ref().unwrap();
-        if is_record_id(&field.ty) {
-            quote! {
-                #field_ident: model.#field_ident.to_string()
-            }
-        } else {
-            quote! {
-                #field_ident: model.#field_ident
-            }
+    let covert_data_fields = fields.iter().map(|ModelField { field, ident, .. }| {
+        match data_conversion(&field.ty, quote!(model.#ident)) {
+            Some((_, value)) => quote! {
+                #ident: #value
+            },
+            None => quote! {
+                #ident: model.#ident
+            },
         }
     });
     let convert_impl = quote! {
-        impl From<#ident> for #data_ident {
+        impl ::std::convert::From<#ident> for #data_ident {
             fn from(model: #ident) -> Self {
                 #data_ident {
                     #(#covert_data_fields),*
@@ -105,9 +166,692 @@ where
             #(#data_fields),*
         }
     };
-    Ok(quote! {
+    quote! {
         #data_struct
 
         #convert_impl
+    }
+}
+
+/// Type of a field in the data struct, with record ids converted to strings,
+/// and the expression converting `value`, `None` when the type is unchanged
+fn data_conversion(ty: &syn::Type, value: TokenStream) -> Option<(syn::Type, TokenStream)> {
+    if is_record_id(ty) {
+        return Some((
+            syn::parse_quote!(::std::string::String),
+            quote!(#value.to_string()),
+        ));
+    }
+    if let Some(inner) = generic_inner(ty, "Option") {
+        let (inner, convert) = data_conversion(inner, quote!(value))?;
+        return Some((
+            syn::parse_quote!(::std::option::Option<#inner>),
+            quote!(#value.map(|value| #convert)),
+        ));
+    }
+    if let Some(inner) = generic_inner(ty, "Vec") {
+        let (inner, convert) = data_conversion(inner, quote!(value))?;
+        return Some((
+            syn::parse_quote!(::std::vec::Vec<#inner>),
+            quote!(#value.into_iter().map(|value| #convert).collect()),
+        ));
+    }
+    None
+}
+
+/// `#[schema(...)]` attribute documenting the validation rules in the OpenAPI schema
+fn validate_schema_attr(ty: &syn::Type, rules: &ValidateArgs) -> Option<syn::Attribute> {
+    let ty = generic_inner(ty, "Option").unwrap_or(ty);
+    let mut hints = vec![];
+    if let Some(length) = &rules.length {
+        let (min, max) = if generic_inner(ty, "Vec").is_some() {
+            (quote!(min_items), quote!(max_items))
+        } else {
+            (quote!(min_length), quote!(max_length))
+        };
+        if let Some(value) = length.min {
+            hints.push(quote!(#min = #value));
+        }
+        if let Some(value) = length.max {
+            hints.push(quote!(#max = #value));
+        }
+    }
+    if rules.email {
+        hints.push(quote!(format = "email"));
+    }
+    if let Some(pattern) = &rules.regex {
+        hints.push(quote!(pattern = #pattern));
+    }
+    if let Some(range) = &rules.range {
+        // Only literals can be written into the schema
+        let literal = |expr: &syn::Expr| match expr {
+            syn::Expr::Lit(_) => true,
+            syn::Expr::Unary(unary) => matches!(*unary.expr, syn::Expr::Lit(_)),
+            _ => false,
+        };
+        if let Some(value) = range.min.as_ref().filter(|value| literal(value)) {
+            hints.push(quote!(minimum = #value));
+        }
+        if let Some(value) = range.max.as_ref().filter(|value| literal(value)) {
+            hints.push(quote!(maximum = #value));
+        }
+    }
+    (!hints.is_empty()).then(|| syn::parse_quote!(#[schema(#(#hints),*)]))
+}
+
+/// Implement `Validate` for `target`, whose fields are the struct fields
+/// passing `filter`, wrapped in `Option` for those passing `optional`
+pub(crate) fn expand_validate_impl(
+    krate: &syn::Path,
+    fields: &[ModelField],
+    target: &Ident,
+    optional: impl Fn(&ModelField) -> bool,
+    filter: impl Fn(&ModelField) -> bool,
+) -> TokenStream {
+    let mut checks = vec![];
+    for model_field in fields.iter().filter(|field| filter(field)) {
+        let ModelField {
+            field,
+            ident: field_ident,
+            args,
+        } = model_field;
+        let Some(rules) = &args.validate else {
+            continue;
+        };
+        let name = field_name(field);
+        let rules = expand_rules(krate, &name, rules);
+        let mut check = quote! { #rules };
+        if generic_inner(&field.ty, "Option").is_some() {
+            check = quote! {
+                if let ::std::option::Option::Some(value) = value {
+                    #check
+                }
+            };
+        }
+        if optional(model_field) {
+            check = quote! {
+                if let ::std::option::Option::Some(value) = &self.#field_ident {
+                    #check
+                }
+            };
+        } else {
+            check = quote! {
+                {
+                    let value = &self.#field_ident;
+                    #check
+                }
+            };
+        }
+        checks.push(check);
+    }
+    let body = if checks.is_empty() {
+        quote! { ::std::result::Result::Ok(()) }
+    } else {
+        quote! {
+            let mut errors = #krate::validation::ValidationErrors::new();
+            #(#checks)*
+            errors.into_result()
+        }
+    };
+    quote! {
+        impl #krate::validation::Validate for #target {
+            fn validate(&self) -> ::std::result::Result<(), #krate::validation::ValidationErrors> {
+                #body
+            }
+        }
+    }
+}
+
+/// Statements checking `value` against the rules and recording failures in `errors`
+fn expand_rules(krate: &syn::Path, name: &str, rules: &ValidateArgs) -> TokenStream {
+    let bound = |value: Option<TokenStream>| match value {
+        Some(value) => quote!(::std::option::Option::Some(#value)),
+        None => quote!(::std::option::Option::None),
+    };
+    let mut checks = vec![];
+    if let Some(length) = &rules.length {
+        let min = bound(length.min.map(|min| quote!(#min)));
+        let max = bound(length.max.map(|max| quote!(#max)));
+        checks.push(quote! {
+            errors.check(#name, "length", #krate::validation::length(value, #min, #max));
+        });
+    }
+    if rules.email {
+        checks.push(quote! {
+            errors.check(#name, "email", #krate::validation::email(value));
+        });
+    }
+    if let Some(pattern) = &rules.regex {
+        checks.push(quote! {
+            {
+                static REGEX: ::std::sync::LazyLock<#krate::validation::Regex> =
+                    ::std::sync::LazyLock::new(|| {
+                        #krate::validation::Regex::new(#pattern)
+                            .expect("invalid `regex` in `#[field(validate)]`")
+                    });
+                errors.check(#name, "regex", #krate::validation::regex(value, &REGEX));
+            }
+        });
+    }
+    if let Some(range) = &rules.range {
+        let min = bound(range.min.as_ref().map(|min| quote!(#min)));
+        let max = bound(range.max.as_ref().map(|max| quote!(#max)));
+        checks.push(quote! {
+            errors.check(#name, "range", #krate::validation::range(value, #min, #max));
+        });
+    }
+    if let Some(custom) = &rules.custom {
+        checks.push(quote! {
+            errors.check(#name, "custom", #custom(value).err());
+        });
+    }
+    quote! { #(#checks)* }
+}
+
+pub(crate) fn expand_fields_struct(
+    krate: &syn::Path,
+    fields: &[ModelField],
+    vis: &Visibility,
+    ident: &Ident,
+    fields_ident: &Ident,
+) -> TokenStream {
+    let fields = fields
+        .iter()
+        .filter(|field| !is_serde_skipped(field.field))
+        .collect::<Vec<_>>();
+    let struct_fields = fields.iter().map(
+        |ModelField {
+             field,
+             ident: field_ident,
+             args,
+         }| {
+            let field_type = &field.ty;
+            match &args.foreign_key {
+                Some(foreign_key) => quote! {
+                    #vis #field_ident: #krate::Link<#ident, #foreign_key>
+                },
+                None => quote! {
+                    #vis #field_ident: #krate::Field<#ident, #field_type>
+                },
+            }
+        },
+    );
+    let field_values = fields.iter().map(
+        |ModelField {
+             field,
+             ident: field_ident,
+             args,
+         }| {
+            let field_name = field_name(field);
+            if args.is_foreign_key() {
+                return quote! {
+                    #field_ident: #krate::Link::new(#field_name)
+                };
+            }
+            match static_kind(&field.ty) {
+                Some((kind, true)) => quote! {
+                    #field_ident: #krate::Field::new(#field_name).cast(#kind)
+                },
+                _ => quote! {
+                    #field_ident: #krate::Field::new(#field_name)
+                },
+            }
+        },
+    );
+    let doc = format!("Typed fields of [`{}`], see [`{}::fields`]", ident, ident);
+    // `Model::fields::email` would need an inherent associated type, which
+    // is unstable, so the fields are a constant accessed as `Model::fields.email`
+    quote! {
+        #[doc = #doc]
+        #vis struct #fields_ident {
+            #(#struct_fields),*
+        }
+
+        impl #ident {
+            /// Fields usable in typed conditions, e.g. `Self::fields.name.eq(value)`
+            #[allow(non_upper_case_globals)]
+            #vis const fields: #fields_ident = #fields_ident {
+                #(#field_values),*
+            };
+        }
+    }
+}
+
+pub(crate) fn expand_schema_fn(
+    krate: &syn::Path,
+    fields: &[ModelField],
+    model_args: &ModelArgs,
+) -> Result<TokenStream, syn::Error> {
+    let mut definitions = vec![];
+    if let Some(RelationArgs { from, to }) = &model_args.relation {
+        definitions.push(quote! {
+            .relation(
+                <#from as #krate::Model>::TABLE_NAME,
+                <#to as #krate::Model>::TABLE_NAME,
+            )
+        });
+    }
+    for ModelField {
+        field,
+        args: field_args,
+        ..
+    } in fields
+    {
+        if field_args.primary || is_builtin_field(field, model_args) || is_serde_skipped(field) {
+            continue;
+        }
+        let field_name = field_name(field);
+        let mut field_schema = match surreal_kind(krate, &field.ty, field_args.foreign_key.as_ref())
+        {
+            SurrealKind::Static { kind, cast: true } => {
+                quote! { #krate::schema::FieldSchema::new(#field_name, #kind).cast() }
+            }
+            SurrealKind::Static { kind, cast: false } => {
+                quote! { #krate::schema::FieldSchema::new(#field_name, #kind) }
+            }
+            SurrealKind::Dynamic(kind) => quote! {{
+                let (kind, flexible) = #kind;
+                let field = #krate::schema::FieldSchema::new(#field_name, kind);
+                if flexible { field.flexible() } else { field }
+            }},
+        };
+        if let Some(value) = generated_value(field, field_args)? {
+            field_schema = quote! { #field_schema.value(#value) };
+        }
+        if field_args.version {
+            // `TYPE` rejects a missing version before `VALUE` can compute it
+            field_schema = quote! { #field_schema.cast() };
+        }
+        definitions.push(quote! {
+            .field(#field_schema)
+        });
+        if field_args.unique || field_args.index {
+            let unique = field_args.unique;
+            definitions.push(quote! {
+                .index(#field_name, #unique)
+            });
+        }
+    }
+    if model_args.soft_delete {
+        definitions.push(quote! {
+            .soft_delete()
+        });
+    }
+    Ok(quote! {
+        fn schema() -> #krate::schema::TableSchema {
+            #krate::schema::TableSchema::new(Self::TABLE_NAME)
+                .schemafull()
+                #(#definitions)*
+        }
+    })
+}
+
+/// Expression filling a `created_at`, `updated_at` or `version` field on write
+pub(crate) fn generated_value(
+    field: &Field,
+    field_args: &FieldArgs,
+) -> Result<Option<&'static str>, syn::Error> {
+    let (value, kind, message) = match (
+        field_args.created_at,
+        field_args.updated_at,
+        field_args.version,
+    ) {
+        (false, false, false) => return Ok(None),
+        (true, false, false) => (
+            "$before OR time::now()",
+            "datetime",
+            "Timestamp field must be of type `DateTime` or `Datetime`",
+        ),
+        (false, true, false) => (
+            "time::now()",
+            "datetime",
+            "Timestamp field must be of type `DateTime` or `Datetime`",
+        ),
+        (false, false, true) => (
+            "($before OR 0) + 1",
+            "int",
+            "Version field must be of an integer type",
+        ),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                field,
+                "Field can only be one of `created_at`, `updated_at` and `version`",
+            ));
+        }
+    };
+    if !matches!(static_kind(&field.ty), Some((field_kind, _)) if field_kind == kind) {
+        return Err(syn::Error::new_spanned(&field.ty, message));
+    }
+    Ok(Some(value))
+}
+
+pub(crate) fn expand_relation_impl(
+    krate: &syn::Path,
+    relation: &RelationArgs,
+    vis: &Visibility,
+    ident: &Ident,
+    input_ident: &Ident,
+) -> TokenStream {
+    let RelationArgs { from, to } = relation;
+    quote! {
+        impl #krate::Relation for #ident {
+            type In = #from;
+            type Out = #to;
+        }
+
+        impl #ident {
+            #vis async fn relate(
+                db: &#krate::SurrealClient,
+                from: impl #krate::IntoRecordId<#from>,
+                to: impl #krate::IntoRecordId<#to>,
+                data: #input_ident,
+            ) -> #krate::Result<::std::option::Option<Self>> {
+                <Self as #krate::Model>::objects(db).relate(from, to, data).await
+            }
+
+            #vis async fn unrelate(
+                db: &#krate::SurrealClient,
+                from: impl #krate::IntoRecordId<#from>,
+                to: impl #krate::IntoRecordId<#to>,
+            ) -> #krate::Result<::std::vec::Vec<Self>> {
+                <Self as #krate::Model>::objects(db).unrelate(from, to).await
+            }
+
+            #vis async fn outgoing(
+                db: &#krate::SurrealClient,
+                from: impl #krate::IntoRecordId<#from>,
+            ) -> #krate::Result<::std::vec::Vec<#to>> {
+                <Self as #krate::Model>::objects(db).outgoing(from).await
+            }
+
+            #vis async fn incoming(
+                db: &#krate::SurrealClient,
+                to: impl #krate::IntoRecordId<#to>,
+            ) -> #krate::Result<::std::vec::Vec<#from>> {
+                <Self as #krate::Model>::objects(db).incoming(to).await
+            }
+        }
+    }
+}
+
+/// Generate has-many accessors on the models targeted by foreign keys
+///
+/// The accessors are methods of a `<Model>RelatedTo<Target>` extension trait
+/// implemented for each target, so that targets defined in other crates can
+/// have them too. An accessor is named `<model>_set` unless `related_name`
+/// is given.
+pub(crate) fn expand_related_methods(
+    krate: &syn::Path,
+    fields: &[ModelField],
+    vis: &Visibility,
+    ident: &Ident,
+) -> Vec<TokenStream> {
+    let default_name = format!("{}_set", ident.to_string().to_snake_case());
+    let mut targets: Vec<(&Ident, Vec<TokenStream>, Vec<TokenStream>)> = vec![];
+    for ModelField {
+        field,
+        args: field_args,
+        ..
+    } in fields
+    {
+        let Some(foreign_key) = &field_args.foreign_key else {
+            continue;
+        };
+        let method_ident = Ident::new(
+            field_args.related_name.as_deref().unwrap_or(&default_name),
+            Span::call_site(),
+        );
+        let field_name = field_name(field);
+        let signature = quote! {
+            fn #method_ident<'c>(&self, client: &'c #krate::SurrealClient) -> #krate::Query<'c, #ident>
+        };
+        let method = quote! {
+            #signature {
+                <#ident as #krate::Model>::objects(client).filter(#krate::Condition::eq(
+                    #field_name,
+                    #krate::Model::record_id(self),
+                ))
+            }
+        };
+        match targets
+            .iter_mut()
+            .find(|(target, ..)| *target == foreign_key)
+        {
+            Some((_, signatures, methods)) => {
+                signatures.push(signature);
+                methods.push(method);
+            }
+            None => targets.push((foreign_key, vec![signature], vec![method])),
+        }
+    }
+    targets
+        .into_iter()
+        .map(|(target, signatures, methods)| {
+            let trait_ident =
+                Ident::new(&format!("{}RelatedTo{}", ident, target), Span::call_site());
+            let doc = format!("Records of `{}` linking to a `{}`", ident, target);
+            quote! {
+                #[doc = #doc]
+                #vis trait #trait_ident {
+                    #(#signatures;)*
+                }
+
+                impl #trait_ident for #target {
+                    #(#methods)*
+                }
+            }
+        })
+        .collect()
+}
+
+/// Register the `ModelMeta` of the model, listed by `registry::models()`
+pub(crate) fn expand_registration(
+    krate: &syn::Path,
+    fields: &[ModelField],
+    ident: &Ident,
+    model_args: &ModelArgs,
+) -> TokenStream {
+    let name = ident.to_string();
+    let table_name = |model: &dyn quote::ToTokens| quote!(<#model as #krate::Model>::TABLE_NAME);
+    let optional = |value: Option<TokenStream>| match value {
+        Some(value) => quote!(::std::option::Option::Some(#value)),
+        None => quote!(::std::option::Option::None),
+    };
+
+    let primary_key = optional(fields.iter().find(|field| field.args.primary).map(|field| {
+        let name = field_name(field.field);
+        quote!(#name)
+    }));
+    let relation = optional(
+        model_args
+            .relation
+            .as_ref()
+            .map(|RelationArgs { from, to }| {
+                let (from, to) = (table_name(from), table_name(to));
+                quote!((#from, #to))
+            }),
+    );
+    let soft_delete = model_args.soft_delete;
+    let field_metas = fields
+        .iter()
+        .filter(|field| !is_serde_skipped(field.field))
+        .map(|ModelField { field, args, .. }| {
+            let name = field_name(field);
+            let ty = type_name(&field.ty);
+            let foreign_key = optional(match &args.foreign_key {
+                Some(foreign_key) => Some(table_name(foreign_key)),
+                None => linked_model(&field.ty).map(|model| table_name(model)),
+            });
+            let FieldArgs {
+                primary,
+                unique,
+                index,
+                created_at,
+                updated_at,
+                version,
+                ..
+            } = *args;
+            quote! {
+                #krate::registry::FieldMeta {
+                    name: #name,
+                    ty: #ty,
+                    foreign_key: #foreign_key,
+                    primary: #primary,
+                    unique: #unique,
+                    index: #index,
+                    created_at: #created_at,
+                    updated_at: #updated_at,
+                    version: #version,
+                }
+            }
+        });
+    let model_table_name = table_name(ident);
+    quote! {
+        #krate::registry::inventory::submit! {
+            #krate::registry::ModelMeta {
+                name: #name,
+                table_name: #model_table_name,
+                primary_key: #primary_key,
+                fields: &[#(#field_metas),*],
+                relation: #relation,
+                soft_delete: #soft_delete,
+                schema: <#ident as #krate::Model>::schema,
+            }
+        }
+    }
+}
+
+/// Implement `FieldKind`: a union of the serialized variant names for enums
+/// of unit variants, a flexible object for structs
+pub(crate) fn expand_field_kind(input: &syn::DeriveInput) -> Result<TokenStream, syn::Error> {
+    let ident = &input.ident;
+    let krate = FieldKindArgs::from_derive_input(input)?.crate_path();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (kind, flexible) = match &input.data {
+        syn::Data::Struct(_) => ("object".to_string(), true),
+        syn::Data::Enum(data) => {
+            let rename_all = SerdeArgs::from_attrs(&input.attrs)?.rename_all;
+            let mut literals = vec![];
+            for variant in &data.variants {
+                if !matches!(variant.fields, syn::Fields::Unit) {
+                    return Err(syn::Error::new_spanned(
+                        variant,
+                        "`FieldKind` can only be derived for enums of unit variants",
+                    ));
+                }
+                let serde_args = SerdeArgs::from_attrs(&variant.attrs)?;
+                if serde_args.skip {
+                    continue;
+                }
+                let name = match (serde_args.rename, &rename_all) {
+                    (Some(name), _) => name,
+                    (None, Some(rule)) => rename_case(&variant.ident.to_string(), rule)
+                        .ok_or_else(|| {
+                            syn::Error::new_spanned(
+                                input,
+                                format!("Unknown `rename_all` rule `{}`", rule),
+                            )
+                        })?,
+                    (None, None) => variant.ident.to_string(),
+                };
+                literals.push(format!(
+                    "'{}'",
+                    name.replace('\\', "\\\\").replace('\'', "\\'")
+                ));
+            }
+            if literals.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    input,
+                    "`FieldKind` enums must have at least one variant",
+                ));
+            }
+            (literals.join(" | "), false)
+        }
+        syn::Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                input,
+                "`FieldKind` can only be derived for structs and enums",
+            ));
+        }
+    };
+    Ok(quote! {
+        impl #impl_generics #krate::schema::FieldKind for #ident #ty_generics #where_clause {
+            const FLEXIBLE: bool = #flexible;
+
+            fn kind() -> ::std::string::String {
+                ::std::string::String::from(#kind)
+            }
+        }
+    })
+}
+
+pub(crate) fn expand_factory(input: &syn::DeriveInput) -> Result<TokenStream, syn::Error> {
+    let ident = &input.ident;
+    let input_ident = Ident::new(&format!("{}Input", ident), Span::call_site());
+    let model_args = ModelArgs::from_derive_input(input)?;
+    let krate = model_args.crate_path();
+    let fields = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => &fields.named,
+        _ => {
+            return Err(syn::Error::new(
+                ident.span(),
+                "`Factory` can only be derived for models",
+            ));
+        }
+    };
+
+    let mut values = vec![];
+    for field in fields {
+        let factory_args = FactoryArgs::from_field(field)?;
+        let is_set = factory_args.default.is_some() || factory_args.sequence.is_some();
+        let field = ModelField {
+            field,
+            ident: field.ident.as_ref().expect("named fields have identifiers"),
+            // Invalid `#[field]` attributes are reported by the `Model` derive
+            args: FieldArgs::from_field(field).unwrap_or_default(),
+        };
+        if !is_input_field(&field, &model_args) {
+            if is_set {
+                return Err(syn::Error::new_spanned(
+                    field.field,
+                    "`#[factory]` has no effect on fields which are not part of the input",
+                ));
+            }
+            continue;
+        }
+        let field_ident = field.ident;
+        let value = match (factory_args.default, factory_args.sequence) {
+            (Some(_), Some(_)) => {
+                return Err(syn::Error::new_spanned(
+                    field.field,
+                    "`default` and `sequence` cannot be used together",
+                ));
+            }
+            (Some(default), None) => quote!(#default),
+            (None, Some(sequence)) => {
+                quote!(::std::convert::Into::into(::std::format!(#sequence)))
+            }
+            (None, None) => quote!(::std::default::Default::default()),
+        };
+        values.push(quote!(#field_ident: #value));
+    }
+
+    Ok(quote! {
+        impl #krate::factory::Factory for #ident {
+            #[allow(unused_variables)]
+            fn definition(n: u64) -> #input_ident {
+                #input_ident {
+                    #(#values),*
+                }
+            }
+
+            fn next_sequence() -> u64 {
+                static SEQUENCE: ::std::sync::atomic::AtomicU64 = ::std::sync::atomic::AtomicU64::new(1);
+                SEQUENCE.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed)
+            }
+        }
     })
 }
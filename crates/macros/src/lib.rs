@@ -1,4 +1,3 @@
-use darling::{FromDeriveInput, FromField};
 use heck::ToSnakeCase;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
@@ -6,120 +5,257 @@ use quote::quote;
 use syn::{DeriveInput, Ident, parse_macro_input};
 
 use crate::{
-    attr::{FieldArgs, ModelArgs},
-    expand::{expand_data_impl, expand_foreign_methods, expand_input_struct},
-    utils::is_record_id,
+    expand::{
+        expand_data_impl, expand_factory, expand_field_kind, expand_fields_struct,
+        expand_foreign_methods, expand_input_struct, expand_patch_struct, expand_registration,
+        expand_related_methods, expand_relation_impl, expand_schema_fn, expand_validate_impl,
+        is_input_field,
+    },
+    model::{expand_model_assertions, parse_model},
+    utils::field_name,
 };
 
 mod attr;
 mod expand;
+mod model;
 mod utils;
 
 #[proc_macro_derive(Model, attributes(model, field))]
 pub fn merak_model(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
-    expand_model(input).unwrap_or_else(|err| err.to_compile_error().into())
+    expand_model(input).unwrap_or_else(|err| err.write_errors().into())
 }
 
-fn expand_model(input: DeriveInput) -> syn::Result<TokenStream> {
+/// Derive `FieldKind` for enums and embedded structs stored in model fields
+#[proc_macro_derive(FieldKind, attributes(field_kind, serde))]
+pub fn merak_field_kind(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_field_kind(&input)
+        .map(Into::into)
+        .unwrap_or_else(|err| err.to_compile_error().into())
+}
+
+/// Derive `Factory` for models, building inputs from `#[factory(...)]` field values
+#[proc_macro_derive(Factory, attributes(factory))]
+pub fn merak_factory(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_factory(&input)
+        .map(Into::into)
+        .unwrap_or_else(|err| err.to_compile_error().into())
+}
+
+fn expand_model(input: DeriveInput) -> darling::Result<TokenStream> {
     let vis = &input.vis;
     let ident = &input.ident;
     let ident_name = ident.to_string();
 
-    let model_args = ModelArgs::from_derive_input(&input)?;
-    let fields = match &input.data {
-        syn::Data::Struct(data) => (&data.fields).into_iter(),
-        _ => {
-            return Err(syn::Error::new(
-                ident.span(),
-                "Only named structs are supported",
-            ));
-        }
-    };
+    let (model_args, fields) = parse_model(&input)?;
+    let krate = &model_args.crate_path();
+    let assertions = expand_model_assertions(krate, &model_args, &fields);
 
-    let foreign_methods = expand_foreign_methods(fields.clone(), vis)?;
+    let foreign_methods = expand_foreign_methods(krate, &fields, vis);
+    let related_methods = expand_related_methods(krate, &fields, vis, ident);
 
     let input_ident = Ident::new(&format!("{}Input", ident), Span::call_site());
-    let input_struct = expand_input_struct(fields.clone(), vis, &input_ident)?;
+    let input_struct = expand_input_struct(&fields, vis, &input_ident, &model_args);
+
+    let patch_ident = Ident::new(&format!("{}Patch", ident), Span::call_site());
+    let patch_struct = expand_patch_struct(&fields, vis, &patch_ident, &model_args);
+
+    let validate_impls = [
+        expand_validate_impl(krate, &fields, ident, |_| false, |_| true),
+        expand_validate_impl(
+            krate,
+            &fields,
+            &input_ident,
+            |field| field.args.version,
+            |field| is_input_field(field, &model_args),
+        ),
+        expand_validate_impl(
+            krate,
+            &fields,
+            &patch_ident,
+            |_| true,
+            |field| is_input_field(field, &model_args),
+        ),
+    ];
 
     let data_ident = Ident::new(&format!("{}Data", ident), Span::call_site());
-    let data_impl = expand_data_impl(fields.clone(), vis, ident, &data_ident)?;
+    let data_impl = expand_data_impl(&fields, vis, ident, &data_ident);
 
-    let table_name = model_args.table_name.unwrap_or(ident_name.to_snake_case());
+    let fields_ident = Ident::new(&format!("{}Fields", ident), Span::call_site());
+    let fields_struct = expand_fields_struct(krate, &fields, vis, ident, &fields_ident);
 
-    let primary_key = fields.clone().find_map(|field| {
-        let field_args = FieldArgs::from_field(field).unwrap();
-        if field_args.primary {
-            Some(field.ident.as_ref().unwrap())
-        } else {
-            None
-        }
-    });
+    let schema_fn = expand_schema_fn(krate, &fields, &model_args)?;
+
+    let registration = expand_registration(krate, &fields, ident, &model_args);
+
+    let relation_impl = model_args
+        .relation
+        .as_ref()
+        .map(|relation| expand_relation_impl(krate, relation, vis, ident, &input_ident));
+
+    let table_name = model_args
+        .table_name
+        .clone()
+        .unwrap_or(ident_name.to_snake_case());
+
+    let primary_key = fields
+        .iter()
+        .find(|field| field.args.primary)
+        .map(|field| field.ident);
 
     let get_by_primary_key = if let Some(primary_key) = primary_key {
         let primary_ident = Ident::new(&format!("get_by_{}", primary_key), Span::call_site());
         quote! {
-            #vis async fn #primary_ident(db: &::merak_core::SurrealClient, id: &str) -> surrealdb::Result<Option<Self>> {
-                db.select((Self::TABLE_NAME, id)).await
+            #vis async fn #primary_ident(
+                db: &#krate::SurrealClient,
+                id: impl #krate::IntoRecordId<Self>,
+            ) -> #krate::Result<::std::option::Option<Self>> {
+                <Self as #krate::Model>::objects(db).get_by_id(id).await
             }
         }
     } else {
         quote! {}
     };
 
+    let version_const = fields.iter().find(|field| field.args.version).map(|field| {
+        let field_name = field_name(field.field);
+        quote! {
+            const VERSION_FIELD: ::std::option::Option<&'static str> =
+                ::std::option::Option::Some(#field_name);
+        }
+    });
+
+    let soft_delete_const = model_args.soft_delete.then(|| {
+        quote! {
+            const SOFT_DELETE: bool = true;
+        }
+    });
+
+    // `#[model(hooks)]` models implement `ModelHooks` themselves
+    let hooks_impl = (!model_args.hooks).then(|| {
+        quote! {
+            impl #krate::ModelHooks for #ident {}
+
+            impl #krate::NoHooks for #ident {}
+        }
+    });
+
+    let record_id_fn = primary_key.map(|primary_key| {
+        quote! {
+            fn record_id(&self) -> ::std::option::Option<#krate::surrealdb::RecordId> {
+                ::std::option::Option::Some(::std::convert::Into::into(self.#primary_key.clone()))
+            }
+        }
+    });
+
     let operations = if let Some(primary_key) = primary_key {
         let primary_ident = Ident::new(&primary_key.to_string(), Span::call_site());
         quote! {
-            #vis async fn save(self, client: &::merak_core::SurrealClient) -> surrealdb::Result<Option<Self>> {
-                client.update(self.#primary_ident.clone()).content(self).await
+            #vis async fn save(self, client: &#krate::SurrealClient) -> #krate::Result<::std::option::Option<Self>> {
+                <Self as #krate::Model>::objects(client).save(self.#primary_ident.clone(), self).await
             }
 
-            #vis async fn delete(self, client: &::merak_core::SurrealClient) -> surrealdb::Result<Option<Self>> {
-                client.delete(self.#primary_ident.clone()).await
+            #vis async fn delete(self, client: &#krate::SurrealClient) -> #krate::Result<::std::option::Option<Self>> {
+                <Self as #krate::Model>::objects(client).delete_record(self.#primary_ident.clone()).await
+            }
+
+            #vis async fn merge(&self, client: &#krate::SurrealClient, patch: #patch_ident) -> #krate::Result<::std::option::Option<Self>> {
+                <Self as #krate::Model>::objects(client).merge_record(self.#primary_ident.clone(), patch).await
             }
         }
     } else {
         quote! {}
     };
 
+    // Transactions cannot run hooks, see `NoHooks`
+    let tx_operations = match primary_key {
+        Some(primary_key) if !model_args.hooks => {
+            let primary_ident = Ident::new(&primary_key.to_string(), Span::call_site());
+            quote! {
+            #vis fn save_tx(self, tx: &mut #krate::transaction::Transaction<'_>) -> #krate::transaction::Pending<::std::option::Option<Self>> {
+                tx.save(self.#primary_ident.clone(), self)
+            }
+
+            #vis fn delete_tx(&self, tx: &mut #krate::transaction::Transaction<'_>) -> #krate::transaction::Pending<::std::option::Option<Self>> {
+                tx.delete(self.#primary_ident.clone())
+            }
+            }
+        }
+        _ => quote! {},
+    };
+
     #[cfg(feature = "utoipa")]
     let trait_impl = quote! {
-        impl ::merak_core::Model for #ident {
+        impl #krate::Model for #ident {
             const TABLE_NAME: &'static str = #table_name;
             type Data = #data_ident;
             type Input = #input_ident;
+            type Patch = #patch_ident;
+            #version_const
+            #soft_delete_const
 
             fn table_name(&self) -> &'static str { Self::TABLE_NAME }
-            fn into_data(self) -> #data_ident { self.into() }
+            fn into_data(self) -> #data_ident { ::std::convert::From::from(self) }
+
+            #record_id_fn
+
+            #schema_fn
         }
     };
     #[cfg(not(feature = "utoipa"))]
     let trait_impl = quote! {
-        impl ::merak_core::Model for #ident {
+        impl #krate::Model for #ident {
             const TABLE_NAME: &'static str = #table_name;
             type Input = #input_ident;
+            type Patch = #patch_ident;
+            #version_const
+            #soft_delete_const
 
             fn table_name(&self) -> &'static str { Self::TABLE_NAME }
+
+            #record_id_fn
+
+            #schema_fn
         }
     };
 
     Ok(quote! {
-        use ::merak_core::prelude::*;
+        #assertions
 
         #input_struct
 
+        #patch_struct
+
+        #(#validate_impls)*
+
         #data_impl
 
+        #fields_struct
+
         #trait_impl
 
+        #hooks_impl
+
+        #relation_impl
+
+        #registration
+
         impl #ident {
             #get_by_primary_key
 
             #operations
 
+            #tx_operations
+
             #(#foreign_methods)*
         }
+
+        #(#related_methods)*
     }
     .into())
 }
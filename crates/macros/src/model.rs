@@ -0,0 +1,165 @@
+use darling::{FromDeriveInput, FromField};
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{DeriveInput, Field, Ident};
+
+use crate::{
+    attr::{FieldArgs, ModelArgs},
+    expand::generated_value,
+    utils::{is_record_id, model_id},
+};
+
+/// A named field of the model with its `#[field(...)]` arguments
+pub(crate) struct ModelField<'a> {
+    pub field: &'a Field,
+    pub ident: &'a Ident,
+    pub args: FieldArgs,
+}
+
+/// Parse the model attributes and fields, reporting every invalid attribute
+/// at once instead of stopping at the first one
+pub(crate) fn parse_model(
+    input: &DeriveInput,
+) -> darling::Result<(ModelArgs, Vec<ModelField<'_>>)> {
+    let mut errors = darling::Error::accumulator();
+    let model_args = errors.handle(ModelArgs::from_derive_input(input));
+
+    let named = match &input.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(named),
+            ..
+        }) => Some(named),
+        syn::Data::Struct(data) => {
+            errors.push(
+                darling::Error::custom("Only structs with named fields are supported")
+                    .with_span(&data.fields),
+            );
+            None
+        }
+        _ => {
+            errors.push(
+                darling::Error::custom(
+                    "Only named structs are supported, derive `FieldKind` for enums stored in fields",
+                )
+                .with_span(&input.ident),
+            );
+            None
+        }
+    };
+
+    let mut fields = vec![];
+    for field in named.into_iter().flat_map(|named| &named.named) {
+        let Some(args) = errors.handle(FieldArgs::from_field(field)) else {
+            continue;
+        };
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+        check_field(field, ident, &args, &mut errors);
+        fields.push(ModelField { field, ident, args });
+    }
+
+    let primaries = fields.iter().filter(|field| field.args.primary);
+    let versions = fields.iter().filter(|field| field.args.version);
+    let duplicates = (primaries.skip(1).map(|field| (field, "primary")))
+        .chain(versions.skip(1).map(|field| (field, "version")));
+    for (duplicate, flag) in duplicates {
+        errors.push(
+            darling::Error::custom(format!("Only one field can be marked as `{}`", flag))
+                .with_span(duplicate.ident),
+        );
+    }
+
+    errors.finish_with((model_args.unwrap_or_default(), fields))
+}
+
+fn check_field(
+    field: &Field,
+    ident: &Ident,
+    args: &FieldArgs,
+    errors: &mut darling::error::Accumulator,
+) {
+    let flags = [
+        ("primary", args.primary),
+        ("foreign_key", args.foreign_key.is_some()),
+        ("created_at", args.created_at),
+        ("updated_at", args.updated_at),
+        ("version", args.version),
+    ];
+    let mut set = flags
+        .iter()
+        .filter(|(_, is_set)| *is_set)
+        .map(|(flag, _)| flag);
+    if let (Some(first), Some(second)) = (set.next(), set.next()) {
+        errors.push(
+            darling::Error::custom(format!(
+                "`{}` and `{}` cannot be used together",
+                first, second
+            ))
+            .with_span(ident),
+        );
+        return;
+    }
+
+    if let Some(foreign_key) = &args.foreign_key {
+        if ident == "id" {
+            errors.push(
+                darling::Error::custom("Foreign key field must NOT be named `id`").with_span(ident),
+            );
+        }
+        if !is_record_id(&field.ty) {
+            errors.push(
+                darling::Error::custom("Foreign key field must be of type `RecordId` or `Id`")
+                    .with_span(&field.ty),
+            );
+        }
+        // `Id<M>` already names the target, it must agree with `foreign_key`
+        let target = model_id(&field.ty).and_then(|model| match model {
+            syn::Type::Path(path) => path.path.segments.last().map(|segment| &segment.ident),
+            _ => None,
+        });
+        if let Some(target) = target.filter(|target| *target != foreign_key) {
+            errors.push(
+                darling::Error::custom(format!(
+                    "`foreign_key` is `{}` but the field is an `Id<{}>`",
+                    foreign_key, target
+                ))
+                .with_span(foreign_key),
+            );
+        }
+    }
+
+    if let Err(err) = generated_value(field, args) {
+        errors.push(err.into());
+    }
+}
+
+/// Assert that the models named in attributes exist and implement `Model`,
+/// reported at the attribute rather than deep inside the generated code
+pub(crate) fn expand_model_assertions(
+    krate: &syn::Path,
+    model_args: &ModelArgs,
+    fields: &[ModelField],
+) -> TokenStream {
+    let targets = fields
+        .iter()
+        .filter_map(|field| field.args.foreign_key.as_ref())
+        .chain(
+            model_args
+                .relation
+                .iter()
+                .flat_map(|relation| [&relation.from, &relation.to]),
+        )
+        .map(|target| {
+            quote_spanned! {target.span()=>
+                assert_model::<#target>();
+            }
+        });
+    quote! {
+        const _: () = {
+            fn assert_model<T: #krate::Model>() {}
+            #[allow(dead_code)]
+            fn assert_models() {
+                #(#targets)*
+            }
+        };
+    }
+}
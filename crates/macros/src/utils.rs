@@ -1,5 +1,13 @@
+use heck::{
+    ToKebabCase, ToLowerCamelCase, ToShoutyKebabCase, ToShoutySnakeCase, ToSnakeCase,
+    ToUpperCamelCase,
+};
+
+use crate::attr::SerdeArgs;
+
+/// Whether the type is a record link, a `RecordId` or an `Id<Model>`
 pub(crate) fn is_record_id(ty: &syn::Type) -> bool {
-    match ty {
+    let is_record_id = match ty {
         syn::Type::Path(path) => path
             .path
             .segments
@@ -7,5 +15,198 @@ pub(crate) fn is_record_id(ty: &syn::Type) -> bool {
             .map(|segment| segment.ident == "RecordId")
             .unwrap_or(false),
         _ => false,
+    };
+    is_record_id || model_id(ty).is_some()
+}
+
+/// Return the model `M` of an `Id<M>`
+pub(crate) fn model_id(ty: &syn::Type) -> Option<&syn::Type> {
+    generic_inner(ty, "Id")
+}
+
+/// Return the model linked by an `Id<M>`, possibly wrapped in `Option` or `Vec`
+pub(crate) fn linked_model(ty: &syn::Type) -> Option<&syn::Type> {
+    model_id(ty)
+        .or_else(|| generic_inner(ty, "Option").and_then(linked_model))
+        .or_else(|| generic_inner(ty, "Vec").and_then(linked_model))
+}
+
+/// Type as written in the source, `Option<Vec<String>>` rather than the
+/// spaced out `Option < Vec < String > >` of the token stream
+pub(crate) fn type_name(ty: &syn::Type) -> String {
+    quote::quote!(#ty)
+        .to_string()
+        .replace(" ::", "::")
+        .replace(":: ", "::")
+        .replace(" <", "<")
+        .replace("< ", "<")
+        .replace(" >", ">")
+        .replace(" ,", ",")
+        .replace("& ", "&")
+}
+
+/// Name of the field in the database, `#[serde(rename)]` or the identifier
+/// without the `r#` prefix of raw identifiers
+pub(crate) fn field_name(field: &syn::Field) -> String {
+    // Malformed `#[serde]` attributes are reported by serde's own derives
+    let serde_args = SerdeArgs::from_attrs(&field.attrs).unwrap_or_default();
+    serde_args
+        .rename
+        .unwrap_or_else(|| syn::ext::IdentExt::unraw(field.ident.as_ref().unwrap()).to_string())
+}
+
+/// Whether the attribute belongs to the derives of this crate and must not be
+/// copied to the generated structs
+pub(crate) fn is_helper_attr(attr: &syn::Attribute) -> bool {
+    attr.path().is_ident("field") || attr.path().is_ident("factory")
+}
+
+/// Whether the field is `#[serde(skip)]` and never stored
+pub(crate) fn is_serde_skipped(field: &syn::Field) -> bool {
+    SerdeArgs::from_attrs(&field.attrs).is_ok_and(|args| args.skip)
+}
+
+/// Apply a `#[serde(rename_all = "...")]` rule to a name
+pub(crate) fn rename_case(name: &str, rule: &str) -> Option<String> {
+    Some(match rule {
+        "lowercase" => name.to_lowercase(),
+        "UPPERCASE" => name.to_uppercase(),
+        "PascalCase" => name.to_upper_camel_case(),
+        "camelCase" => name.to_lower_camel_case(),
+        "snake_case" => name.to_snake_case(),
+        "SCREAMING_SNAKE_CASE" => name.to_shouty_snake_case(),
+        "kebab-case" => name.to_kebab_case(),
+        "SCREAMING-KEBAB-CASE" => name.to_shouty_kebab_case(),
+        _ => return None,
+    })
+}
+
+/// Whether the field holds the `in` or `out` record of an edge
+pub(crate) fn is_relation_endpoint(field: &syn::Field) -> bool {
+    matches!(field_name(field).as_str(), "in" | "out")
+}
+
+/// Return the generic argument of a single-parameter type such as `Option<T>` or `Vec<T>`
+pub(crate) fn generic_inner<'a>(ty: &'a syn::Type, wrapper: &str) -> Option<&'a syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// SurrealQL type of a field together with how it is enforced
+pub(crate) enum SurrealKind {
+    /// Type known from the Rust type, with whether the field must be cast
+    /// with `VALUE <kind> $value`
+    Static { kind: String, cast: bool },
+    /// Type of record links or types implementing `FieldKind`, an expression
+    /// evaluating to the type as a `String` and whether the field is flexible
+    Dynamic(proc_macro2::TokenStream),
+}
+
+/// Map a Rust field type to the SurrealQL type used in `DEFINE FIELD`
+///
+/// Record links take the table name from the foreign model at runtime and
+/// other types may implement `FieldKind`, so their kind is an expression.
+pub(crate) fn surreal_kind(
+    krate: &syn::Path,
+    ty: &syn::Type,
+    foreign_key: Option<&syn::Ident>,
+) -> SurrealKind {
+    match static_kind(ty) {
+        Some((kind, cast)) => SurrealKind::Static { kind, cast },
+        None => SurrealKind::Dynamic(dynamic_kind(krate, ty, foreign_key)),
     }
 }
+
+fn dynamic_kind(
+    krate: &syn::Path,
+    ty: &syn::Type,
+    foreign_key: Option<&syn::Ident>,
+) -> proc_macro2::TokenStream {
+    if let Some(inner) = generic_inner(ty, "Option") {
+        let inner = dynamic_kind(krate, inner, foreign_key);
+        return quote::quote! {{
+            let (kind, flexible) = #inner;
+            (::std::format!("option<{}>", kind), flexible)
+        }};
+    }
+    if let Some(inner) = generic_inner(ty, "Vec") {
+        let inner = dynamic_kind(krate, inner, foreign_key);
+        return quote::quote! {{
+            let (kind, flexible) = #inner;
+            (::std::format!("array<{}>", kind), flexible)
+        }};
+    }
+    if let Some((kind, _)) = static_kind(ty) {
+        return quote::quote! { (::std::string::String::from(#kind), false) };
+    }
+    let linked = foreign_key
+        .map(|foreign_key| quote::quote!(#foreign_key))
+        .or_else(|| model_id(ty).map(|model| quote::quote!(#model)));
+    match linked {
+        Some(linked) if is_record_id(ty) => quote::quote! {
+            (
+                ::std::format!("record<{}>", <#linked as #krate::Model>::TABLE_NAME),
+                false,
+            )
+        },
+        _ if is_record_id(ty) => quote::quote! { (::std::string::String::from("record"), false) },
+        _ => quote::quote! {{
+            #[allow(unused_imports)]
+            use #krate::schema::{AnyKind as _, DeclaredKind as _};
+            (&&#krate::schema::KindOf::<#ty>::new()).kind()
+        }},
+    }
+}
+
+/// Map a type without record links to its SurrealQL type
+///
+/// Returns `None` for record links and types unknown to the derive, and
+/// whether values of the type serialize as strings and have to be cast on
+/// write (e.g. `chrono::DateTime`).
+pub(crate) fn static_kind(ty: &syn::Type) -> Option<(String, bool)> {
+    if let Some(inner) = generic_inner(ty, "Option") {
+        let (inner, cast) = static_kind(inner)?;
+        return Some((format!("option<{}>", inner), cast));
+    }
+    if let Some(inner) = generic_inner(ty, "Vec") {
+        let (inner, cast) = static_kind(inner)?;
+        return Some((format!("array<{}>", inner), cast));
+    }
+    if is_record_id(ty) {
+        return None;
+    }
+    let ident = match ty {
+        syn::Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .map(|segment| segment.ident.to_string()),
+        _ => None,
+    };
+    let (kind, cast) = match ident.as_deref() {
+        Some("String" | "str") => ("string", false),
+        Some("bool") => ("bool", false),
+        Some("i8" | "i16" | "i32" | "i64" | "isize" | "u8" | "u16" | "u32" | "u64" | "usize") => {
+            ("int", false)
+        }
+        Some("f32" | "f64") => ("float", false),
+        Some("Datetime") => ("datetime", false),
+        Some("DateTime") => ("datetime", true),
+        Some("Decimal") => ("decimal", true),
+        Some("Uuid") => ("uuid", true),
+        _ => return None,
+    };
+    Some((kind.to_string(), cast))
+}
@@ -0,0 +1,131 @@
+use merak_core::schema::Schema;
+use merak_core::testing::TestDb;
+use merak_core::{Group, Id, Model};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "projects")]
+struct Project {
+    #[field(primary)]
+    id: Id<Project>,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "tasks")]
+struct Task {
+    #[field(primary)]
+    id: Id<Task>,
+    #[field(foreign_key = Project)]
+    project_id: Id<Project>,
+    status: String,
+    points: i64,
+}
+
+async fn setup() -> TestDb {
+    let db = TestDb::with_schema(Schema::new().register::<Project>().register::<Task>())
+        .await
+        .unwrap();
+    for key in ["merak", "other"] {
+        db.fixture_with_id::<Project>(key, ProjectInput {})
+            .await
+            .unwrap();
+    }
+    for (project, status, points) in [
+        ("merak", "done", 1),
+        ("merak", "done", 2),
+        ("merak", "open", 4),
+        ("other", "open", 8),
+    ] {
+        let input = TaskInput {
+            project_id: Id::new(project),
+            status: status.to_string(),
+            points,
+        };
+        db.fixture::<Task>(input).await.unwrap();
+    }
+    db
+}
+
+fn sorted<K: Ord, V>(mut groups: Vec<Group<K, V>>) -> Vec<(K, V)> {
+    groups.sort_by(|a, b| a.key.cmp(&b.key));
+    groups
+        .into_iter()
+        .map(|group| (group.key, group.value))
+        .collect()
+}
+
+#[tokio::test]
+pub async fn aggregate() {
+    let db = setup().await;
+    let fields = Task::fields;
+    let objects = Task::objects(&db);
+
+    assert_eq!(objects.query().sum(fields.points).await.unwrap(), 15);
+    let merak = objects.filter(fields.project_id.eq(Id::new("merak")));
+    assert_eq!(merak.sum(fields.points).await.unwrap(), 7);
+    let mean = merak.mean(fields.points).await.unwrap().unwrap();
+    assert!((mean - 7.0 / 3.0).abs() < 1e-9);
+
+    // Nothing matches
+    let none = objects.filter(fields.points.gt(100));
+    assert_eq!(none.sum(fields.points).await.unwrap(), 0);
+    assert_eq!(none.mean(fields.points).await.unwrap(), None);
+}
+
+#[tokio::test]
+pub async fn group_by() {
+    let db = setup().await;
+    let fields = Task::fields;
+    let objects = Task::objects(&db);
+
+    let counts = objects
+        .query()
+        .group_by(fields.status)
+        .count()
+        .await
+        .unwrap();
+    assert_eq!(
+        sorted(counts),
+        [("done".to_string(), 2), ("open".to_string(), 2)]
+    );
+
+    let sums = objects
+        .filter(fields.points.gt(1))
+        .group_by(fields.status)
+        .sum(fields.points)
+        .await
+        .unwrap();
+    assert_eq!(
+        sorted(sums),
+        [("done".to_string(), 2), ("open".to_string(), 12)]
+    );
+
+    let means = objects
+        .query()
+        .group_by(fields.status)
+        .mean(fields.points)
+        .await
+        .unwrap();
+    assert_eq!(
+        sorted(means),
+        [("done".to_string(), 1.5), ("open".to_string(), 6.0)]
+    );
+
+    // Links are grouped by the record they point to
+    let per_project = objects
+        .query()
+        .group_by(fields.project_id)
+        .count()
+        .await
+        .unwrap();
+    let mut per_project: Vec<(String, u64)> = per_project
+        .into_iter()
+        .map(|group| (group.key.key().to_string(), group.value))
+        .collect();
+    per_project.sort();
+    assert_eq!(
+        per_project,
+        [("merak".to_string(), 3), ("other".to_string(), 1)]
+    );
+}
@@ -0,0 +1,96 @@
+use futures::stream;
+use merak_core::schema::Schema;
+use merak_core::testing::TestDb;
+use merak_core::{Error, INSERT_CHUNK_SIZE, Id, Model};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "tasks")]
+struct Task {
+    #[field(primary)]
+    id: Id<Task>,
+    #[field(validate(length(min = 1)))]
+    title: String,
+    points: i64,
+}
+
+async fn setup() -> TestDb {
+    TestDb::with_schema(Schema::new().register::<Task>())
+        .await
+        .unwrap()
+}
+
+fn input(n: usize) -> TaskInput {
+    TaskInput {
+        title: format!("task {}", n),
+        points: (n % 3) as i64,
+    }
+}
+
+#[tokio::test]
+pub async fn insert_in_chunks() {
+    let db = setup().await;
+    let objects = Task::objects(&db);
+
+    let count = INSERT_CHUNK_SIZE * 2 + 1;
+    let inserted = objects.insert_many((0..count).map(input)).await.unwrap();
+    assert_eq!(inserted, count as u64);
+
+    let inserted = objects
+        .insert_stream(stream::iter((0..10).map(input)))
+        .await
+        .unwrap();
+    assert_eq!(inserted, 10);
+    assert_eq!(objects.query().count().await.unwrap(), count as u64 + 10);
+}
+
+#[tokio::test]
+pub async fn insert_validates_inputs() {
+    let db = setup().await;
+    let objects = Task::objects(&db);
+
+    let mut inputs: Vec<_> = (0..3).map(input).collect();
+    inputs[1].title = String::new();
+    let inserted = objects.insert_many(inputs).await;
+    assert!(matches!(inserted, Err(Error::Invalid(_))));
+    // The chunk holding the invalid input is not inserted
+    assert_eq!(objects.query().count().await.unwrap(), 0);
+}
+
+#[tokio::test]
+pub async fn update_and_delete_where() {
+    let db = setup().await;
+    let fields = Task::fields;
+    let objects = Task::objects(&db);
+    objects.insert_many((0..9).map(input)).await.unwrap();
+
+    let patch = TaskPatch {
+        points: Some(10),
+        ..Default::default()
+    };
+    let updated = objects
+        .update_where(fields.points.eq(0), patch)
+        .await
+        .unwrap();
+    assert_eq!(updated, 3);
+    assert_eq!(
+        objects.filter(fields.points.eq(10)).count().await.unwrap(),
+        3
+    );
+
+    let deleted = objects.delete_where(fields.points.lt(2)).await.unwrap();
+    assert_eq!(deleted, 3);
+    assert_eq!(objects.query().count().await.unwrap(), 6);
+
+    // Nothing matches
+    let patch = TaskPatch {
+        title: Some("none".to_string()),
+        ..Default::default()
+    };
+    let updated = objects
+        .update_where(fields.points.gt(100), patch)
+        .await
+        .unwrap();
+    assert_eq!(updated, 0);
+}
@@ -1,6 +1,7 @@
 #[cfg(feature = "utoipa")]
 #[test]
 pub fn data_model() {
+    use merak_core::Model;
     use merak_macros::Model;
     use serde::{Deserialize, Serialize};
     use surrealdb::RecordId;
@@ -0,0 +1,49 @@
+use merak_core::{Factory, Validate};
+use merak_macros::{Factory, Model};
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[derive(Model, Factory, Serialize, Deserialize)]
+#[model(table_name = "any_table")]
+struct AnyModel {
+    #[field(primary)]
+    id: RecordId,
+    #[field(validate(length(min = 3)))]
+    #[factory(sequence = "user{n}")]
+    username: String,
+    #[factory(sequence = "user{n}@example.com")]
+    email: Option<String>,
+    #[factory(default = 18)]
+    age: u32,
+    tags: Vec<String>,
+    #[field(skip_input)]
+    login_count: Option<u32>,
+}
+
+#[test]
+pub fn definition() {
+    let input = AnyModel::definition(7);
+    assert_eq!(input.username, "user7");
+    assert_eq!(input.email.as_deref(), Some("user7@example.com"));
+    assert_eq!(input.age, 18);
+    assert!(input.tags.is_empty());
+    assert!(input.validate().is_ok());
+}
+
+#[test]
+pub fn sequences_and_overrides() {
+    let factory = AnyModel::factory()
+        .with(|input| input.age = 30)
+        .sequence(|input, n| input.tags = vec![format!("tag{}", n)]);
+    let inputs = factory.build_many(2);
+
+    // Each build takes the next number of the sequence
+    let n = inputs[0]
+        .username
+        .trim_start_matches("user")
+        .parse::<u64>()
+        .unwrap();
+    assert_eq!(inputs[1].username, format!("user{}", n + 1));
+    assert_eq!(inputs[1].tags, vec![format!("tag{}", n + 1)]);
+    assert!(inputs.iter().all(|input| input.age == 30));
+}
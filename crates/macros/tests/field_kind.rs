@@ -0,0 +1,158 @@
+use merak_core::{FieldKind, Model};
+use merak_macros::{FieldKind, Model};
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+use utoipa::ToSchema;
+
+#[derive(FieldKind, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Todo,
+    InProgress,
+    #[serde(rename = "finished")]
+    Done,
+}
+
+#[derive(FieldKind, Serialize, Deserialize, ToSchema)]
+enum Priority {
+    Low,
+    High,
+}
+
+#[derive(FieldKind, Serialize, Deserialize, ToSchema)]
+struct Address {
+    city: String,
+    zip: Option<String>,
+}
+
+#[test]
+pub fn enum_kinds() {
+    assert_eq!(Status::kind(), "'todo' | 'in_progress' | 'finished'");
+    assert_eq!(Priority::kind(), "'Low' | 'High'");
+    assert_eq!(Address::kind(), "object");
+    const { assert!(!Status::FLEXIBLE && Address::FLEXIBLE) };
+}
+
+#[test]
+pub fn nested_types() {
+    #[derive(Model, Serialize, Deserialize)]
+    #[model(table_name = "any_table")]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        status: Status,
+        priority: Option<Priority>,
+        labels: Vec<Status>,
+        address: Address,
+        previous: Vec<Address>,
+        extra: serde_json::Value,
+    }
+
+    assert_eq!(
+        AnyModel::schema().statements(),
+        vec![
+            "DEFINE TABLE OVERWRITE any_table SCHEMAFULL",
+            "DEFINE FIELD OVERWRITE status ON TABLE any_table TYPE 'todo' | 'in_progress' | 'finished'",
+            "DEFINE FIELD OVERWRITE priority ON TABLE any_table TYPE option<'Low' | 'High'>",
+            "DEFINE FIELD OVERWRITE labels ON TABLE any_table TYPE array<'todo' | 'in_progress' | 'finished'>",
+            "DEFINE FIELD OVERWRITE address ON TABLE any_table FLEXIBLE TYPE object",
+            "DEFINE FIELD OVERWRITE previous ON TABLE any_table FLEXIBLE TYPE array<object>",
+            "DEFINE FIELD OVERWRITE extra ON TABLE any_table FLEXIBLE TYPE any",
+        ]
+    );
+}
+
+#[test]
+pub fn serde_attributes() {
+    #[allow(dead_code)]
+    #[derive(Model, Serialize, Deserialize)]
+    #[model(table_name = "any_table")]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        #[serde(rename = "displayName")]
+        display_name: String,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(skip)]
+        cached: Option<String>,
+        #[field(version)]
+        #[serde(default)]
+        version: u64,
+    }
+
+    assert_eq!(AnyModel::fields.display_name.name(), "displayName");
+    assert_eq!(
+        AnyModel::schema().statements(),
+        vec![
+            "DEFINE TABLE OVERWRITE any_table SCHEMAFULL",
+            "DEFINE FIELD OVERWRITE displayName ON TABLE any_table TYPE string",
+            "DEFINE FIELD OVERWRITE tags ON TABLE any_table TYPE array<string>",
+            "DEFINE FIELD OVERWRITE version ON TABLE any_table VALUE <int> (($before OR 0) + 1)",
+        ]
+    );
+
+    let input: AnyModelInput = serde_json::from_value(serde_json::json!({
+        "displayName": "merak",
+    }))
+    .unwrap();
+    assert!(input.tags.is_empty());
+    assert_eq!(input.version, None);
+    assert_eq!(
+        serde_json::to_value(&input).unwrap(),
+        serde_json::json!({ "displayName": "merak", "tags": [] })
+    );
+}
+
+#[test]
+pub fn skip_input_and_data() {
+    #[allow(dead_code)]
+    #[derive(Model, Serialize, Deserialize)]
+    #[model(table_name = "any_table")]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        username: String,
+        #[field(skip_data)]
+        password_hash: String,
+        #[field(skip_input)]
+        login_count: Option<u32>,
+        owner: Option<RecordId>,
+        members: Vec<RecordId>,
+    }
+
+    let input = AnyModelInput {
+        username: "merak".to_string(),
+        password_hash: "hash".to_string(),
+        owner: None,
+        members: vec![],
+    };
+    assert_eq!(
+        serde_json::to_value(&input).unwrap(),
+        serde_json::json!({
+            "username": "merak",
+            "password_hash": "hash",
+            "owner": null,
+            "members": [],
+        })
+    );
+
+    let data = AnyModelData::from(AnyModel {
+        id: RecordId::from(("any_table", "1")),
+        username: "merak".to_string(),
+        password_hash: "hash".to_string(),
+        login_count: Some(1),
+        owner: Some(RecordId::from(("users", "1"))),
+        members: vec![RecordId::from(("users", "2"))],
+    });
+    assert_eq!(
+        serde_json::to_value(&data).unwrap(),
+        serde_json::json!({
+            "id": "any_table:⟨1⟩",
+            "username": "merak",
+            "login_count": 1,
+            "owner": "users:⟨1⟩",
+            "members": ["users:⟨2⟩"],
+        })
+    );
+}
@@ -0,0 +1,65 @@
+use merak_core::Field;
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[test]
+pub fn field_names() {
+    #[derive(Model, Serialize, Deserialize)]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        name: String,
+        age: u32,
+    }
+
+    assert_eq!(AnyModel::fields.id.name(), "id");
+    assert_eq!(AnyModel::fields.name.name(), "name");
+    assert_eq!(AnyModel::fields.age.name(), "age");
+}
+
+#[test]
+pub fn field_types() {
+    #[derive(Model, Serialize, Deserialize)]
+    struct AnyModel {
+        name: String,
+        age: u32,
+    }
+
+    let name: Field<AnyModel, String> = AnyModel::fields.name;
+    let age: Field<AnyModel, u32> = AnyModel::fields.age;
+
+    let _ = name.eq("merak").or(age.gte(18u32));
+    assert_eq!(String::from(age), "age");
+}
+
+#[test]
+pub fn patch_struct() {
+    #[allow(dead_code)]
+    #[derive(Model, Serialize, Deserialize)]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        name: String,
+        nickname: Option<String>,
+    }
+
+    let patch = AnyModelPatch {
+        name: Some("merak".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(patch.nickname, None);
+    assert_eq!(
+        serde_json::to_value(&patch).unwrap(),
+        serde_json::json!({ "name": "merak" })
+    );
+
+    let patch = AnyModelPatch {
+        nickname: Some(None),
+        ..Default::default()
+    };
+    assert_eq!(
+        serde_json::to_value(&patch).unwrap(),
+        serde_json::json!({ "nickname": null })
+    );
+}
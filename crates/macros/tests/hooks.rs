@@ -0,0 +1,62 @@
+use futures::executor::block_on;
+use merak_core::ModelHooks;
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[test]
+pub fn default_hooks() {
+    #[derive(Model, Serialize, Deserialize)]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        name: String,
+    }
+
+    let client = merak_core::SurrealClient::init();
+    let mut input = AnyModelInput {
+        name: "merak".to_string(),
+    };
+    block_on(AnyModel::before_create(&client, &mut input)).unwrap();
+    assert_eq!(input.name, "merak");
+}
+
+#[test]
+pub fn custom_hooks() {
+    #[derive(Model, Serialize, Deserialize)]
+    #[model(hooks)]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        name: String,
+    }
+
+    impl ModelHooks for AnyModel {
+        async fn before_create(
+            _client: &merak_core::SurrealClient,
+            input: &mut AnyModelInput,
+        ) -> merak_core::Result<()> {
+            input.name = input.name.trim().to_lowercase();
+            Ok(())
+        }
+
+        async fn before_delete(
+            _client: &merak_core::SurrealClient,
+            _id: &RecordId,
+        ) -> merak_core::Result<()> {
+            Err(merak_core::Error::Validation(
+                "Records can't be deleted".to_string(),
+            ))
+        }
+    }
+
+    let client = merak_core::SurrealClient::init();
+    let mut input = AnyModelInput {
+        name: " Merak ".to_string(),
+    };
+    block_on(AnyModel::before_create(&client, &mut input)).unwrap();
+    assert_eq!(input.name, "merak");
+
+    let id = RecordId::from(("any_model", "merak"));
+    assert!(block_on(AnyModel::before_delete(&client, &id)).is_err());
+}
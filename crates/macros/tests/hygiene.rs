@@ -0,0 +1,70 @@
+use merak_core::Model as _;
+
+/// `merak_core` re-exported under another path
+mod merak {
+    pub use merak_core::*;
+}
+
+/// Models next to items named like those used by the derived code
+#[allow(dead_code)]
+mod shadowed {
+    use merak_macros::{Factory, FieldKind, Model};
+    use serde::{Deserialize, Serialize};
+    use utoipa::ToSchema;
+
+    use crate::merak::Id;
+    use crate::merak::surrealdb::RecordId;
+
+    pub struct Model;
+    pub mod surrealdb {}
+    pub mod merak_core {}
+    pub type Result<T> = std::result::Result<T, ()>;
+    pub type Option = ();
+
+    #[derive(FieldKind, Serialize, Deserialize, ToSchema)]
+    #[field_kind(crate = "crate::merak")]
+    pub enum Genre {
+        Fiction,
+        Poetry,
+    }
+
+    #[derive(Model, Factory, Serialize, Deserialize)]
+    #[model(crate = "crate::merak")]
+    pub struct Author {
+        #[field(primary)]
+        pub id: Id<Author>,
+        #[field(validate(length(min = 1)))]
+        #[factory(sequence = "author{n}")]
+        pub name: String,
+    }
+
+    #[derive(Model, Serialize, Deserialize)]
+    #[model(crate = "crate::merak")]
+    pub struct Book {
+        #[field(primary)]
+        pub id: RecordId,
+        #[field(foreign_key = Author, related_name = "books")]
+        pub author_id: Id<Author>,
+        pub genre: Genre,
+        #[field(version)]
+        pub version: u64,
+    }
+}
+
+#[test]
+pub fn shadowed_names_and_crate_path() {
+    use merak_core::Factory;
+    use shadowed::{Author, Book};
+
+    assert_eq!(Author::factory().build().name.len(), "author".len() + 1);
+    assert_eq!(Book::VERSION_FIELD, Some("version"));
+    assert_eq!(
+        Book::schema().statements(),
+        vec![
+            "DEFINE TABLE OVERWRITE book SCHEMAFULL",
+            "DEFINE FIELD OVERWRITE author_id ON TABLE book TYPE record<author>",
+            "DEFINE FIELD OVERWRITE genre ON TABLE book TYPE 'Fiction' | 'Poetry'",
+            "DEFINE FIELD OVERWRITE version ON TABLE book VALUE <int> (($before OR 0) + 1)",
+        ]
+    );
+}
@@ -0,0 +1,49 @@
+use merak_core::{Id, Model};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "users")]
+struct User {
+    #[field(primary)]
+    id: Id<User>,
+    name: String,
+}
+
+#[allow(dead_code)]
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "sessions")]
+struct Session {
+    #[field(primary)]
+    id: Id<Session>,
+    #[field(foreign_key = User)]
+    user_id: Id<User>,
+    reviewer: Option<Id<User>>,
+}
+
+#[test]
+pub fn schema() {
+    assert_eq!(
+        Session::schema().statements(),
+        vec![
+            "DEFINE TABLE OVERWRITE sessions SCHEMAFULL",
+            "DEFINE FIELD OVERWRITE user_id ON TABLE sessions TYPE record<users>",
+            "DEFINE FIELD OVERWRITE reviewer ON TABLE sessions TYPE option<record<users>>",
+        ]
+    );
+}
+
+#[test]
+pub fn record_id() {
+    let user = User {
+        id: Id::new("1b2c-3d"),
+        name: "merak".to_string(),
+    };
+    assert_eq!(user.record_id(), Some(RecordId::from(("users", "1b2c-3d"))));
+    let _ = User::fields.id.eq(user.id.clone());
+    let _ = Session::fields.user_id.eq(user.id.clone());
+
+    let data = UserData::from(user);
+    assert_eq!(data.id, "1b2c-3d");
+}
@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use merak_core::schema::Schema;
+use merak_core::testing::TestDb;
+use merak_core::{Action, Id, LiveStream, Model};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "tasks")]
+struct Task {
+    #[field(primary)]
+    id: Id<Task>,
+    project: String,
+    points: i64,
+}
+
+fn input(project: &str, points: i64) -> TaskInput {
+    TaskInput {
+        project: project.to_string(),
+        points,
+    }
+}
+
+async fn next(changes: &mut LiveStream<Task>) -> (Action, String, i64) {
+    let notification = tokio::time::timeout(Duration::from_secs(5), changes.next())
+        .await
+        .expect("no notification")
+        .unwrap()
+        .unwrap();
+    let task = notification.data;
+    (notification.action, task.id.key().to_string(), task.points)
+}
+
+#[tokio::test]
+pub async fn live_filtered_changes() {
+    let db = TestDb::with_schema(Schema::new().register::<Task>())
+        .await
+        .unwrap();
+    let fields = Task::fields;
+    let objects = Task::objects(&db);
+    // The parameters are inlined, including a value that needs escaping
+    let mut changes = objects
+        .filter(fields.project.eq("merak's".to_string()))
+        .filter(fields.points.gt(1))
+        .live()
+        .await
+        .unwrap();
+
+    objects
+        .create_with_id("other".to_string(), input("other", 5))
+        .await
+        .unwrap();
+    objects
+        .create_with_id("small".to_string(), input("merak's", 1))
+        .await
+        .unwrap();
+    objects
+        .create_with_id("a".to_string(), input("merak's", 2))
+        .await
+        .unwrap();
+    objects
+        .update(Id::<Task>::new("a"), input("merak's", 3))
+        .await
+        .unwrap();
+    objects.delete(Id::<Task>::new("a")).await.unwrap();
+
+    assert_eq!(
+        next(&mut changes).await,
+        (Action::Create, "a".to_string(), 2)
+    );
+    assert_eq!(
+        next(&mut changes).await,
+        (Action::Update, "a".to_string(), 3)
+    );
+    assert_eq!(
+        next(&mut changes).await,
+        (Action::Delete, "a".to_string(), 3)
+    );
+}
@@ -0,0 +1,143 @@
+use chrono::{DateTime, TimeZone, Utc};
+use merak_core::schema::Schema;
+use merak_core::testing::TestDb;
+use merak_core::{Error, Id, Model, Order, Page};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "tasks")]
+struct Task {
+    #[field(primary)]
+    id: Id<Task>,
+    points: i64,
+    due_at: DateTime<Utc>,
+}
+
+/// Tasks `a` to `e`, with ties on both sort keys
+async fn setup() -> TestDb {
+    let db = TestDb::with_schema(Schema::new().register::<Task>())
+        .await
+        .unwrap();
+    for (key, points, day) in [
+        ("a", 1, 3),
+        ("b", 2, 1),
+        ("c", 2, 2),
+        ("d", 2, 2),
+        ("e", 3, 1),
+    ] {
+        let input = TaskInput {
+            points,
+            due_at: Utc.with_ymd_and_hms(2025, 1, day, 0, 0, 0).unwrap(),
+        };
+        db.fixture_with_id::<Task>(key, input).await.unwrap();
+    }
+    db
+}
+
+fn keys(page: &Page<Task>) -> Vec<String> {
+    page.items
+        .iter()
+        .map(|task| task.id.key().to_string())
+        .collect()
+}
+
+#[tokio::test]
+pub async fn paginate_by_id() {
+    let db = setup().await;
+    let objects = Task::objects(&db);
+
+    let first = objects
+        .query()
+        .paginate(2)
+        .with_total()
+        .fetch()
+        .await
+        .unwrap();
+    assert_eq!(keys(&first), ["a", "b"]);
+    assert!(first.has_more);
+    assert_eq!(first.total, Some(5));
+
+    let second = objects
+        .query()
+        .paginate(2)
+        .after(first.next_cursor.as_deref())
+        .fetch()
+        .await
+        .unwrap();
+    assert_eq!(keys(&second), ["c", "d"]);
+    assert_eq!(second.total, None);
+
+    let last = objects
+        .query()
+        .paginate(2)
+        .after(second.next_cursor.as_deref())
+        .fetch()
+        .await
+        .unwrap();
+    assert_eq!(keys(&last), ["e"]);
+    assert!(!last.has_more);
+    assert_eq!(last.next_cursor, None);
+}
+
+#[tokio::test]
+pub async fn paginate_breaks_ties_by_id() {
+    let db = setup().await;
+    let fields = Task::fields;
+
+    let mut cursor = None;
+    let mut pages = Vec::new();
+    loop {
+        let page = Task::objects(&db)
+            .filter(fields.points.gt(1))
+            .paginate(2)
+            .order_by(fields.points, Order::Desc)
+            .after(cursor.as_deref())
+            .fetch()
+            .await
+            .unwrap();
+        pages.push(keys(&page));
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    // `b`, `c` and `d` share their points and are split across pages
+    assert_eq!(pages, [vec!["e", "d"], vec!["c", "b"]]);
+}
+
+async fn by_due_at(db: &TestDb, cursor: Option<String>) -> Page<Task> {
+    Task::objects(db)
+        .query()
+        .paginate(2)
+        .order_by(Task::fields.due_at, Order::Asc)
+        .after(cursor.as_deref())
+        .fetch()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+pub async fn paginate_by_datetime() {
+    let db = setup().await;
+
+    let first = by_due_at(&db, None).await;
+    assert_eq!(keys(&first), ["b", "e"]);
+    let second = by_due_at(&db, first.next_cursor).await;
+    assert_eq!(keys(&second), ["c", "d"]);
+    let last = by_due_at(&db, second.next_cursor).await;
+    assert_eq!(keys(&last), ["a"]);
+    assert!(!last.has_more);
+}
+
+#[tokio::test]
+pub async fn paginate_rejects_invalid_cursor() {
+    let db = setup().await;
+    let page = Task::objects(&db)
+        .query()
+        .paginate(2)
+        .after(Some("not a cursor"))
+        .fetch()
+        .await;
+    assert!(matches!(page, Err(Error::Validation(_))));
+}
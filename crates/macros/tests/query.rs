@@ -0,0 +1,149 @@
+use chrono::{DateTime, Utc};
+use merak_core::schema::Schema;
+use merak_core::testing::TestDb;
+use merak_core::{Id, Model, Order};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "tasks", soft_delete)]
+struct Task {
+    #[field(primary)]
+    id: Id<Task>,
+    title: String,
+    points: i64,
+    tags: Vec<String>,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+async fn setup() -> TestDb {
+    let db = TestDb::with_schema(Schema::new().register::<Task>())
+        .await
+        .unwrap();
+    for (key, points, tags) in [
+        ("a", 1, vec!["bug"]),
+        ("b", 2, vec!["bug", "ui"]),
+        ("c", 3, vec!["ui"]),
+        ("d", 4, vec![]),
+    ] {
+        let input = TaskInput {
+            title: format!("task {}", key),
+            points,
+            tags: tags.into_iter().map(str::to_string).collect(),
+        };
+        db.fixture_with_id::<Task>(key, input).await.unwrap();
+    }
+    db
+}
+
+fn keys(tasks: &[Task]) -> Vec<String> {
+    tasks.iter().map(|task| task.id.key().to_string()).collect()
+}
+
+#[tokio::test]
+pub async fn query_builder() {
+    let db = setup().await;
+    let fields = Task::fields;
+    let objects = Task::objects(&db);
+
+    let tasks = objects
+        .filter(fields.points.gte(2))
+        .order_by("points", Order::Desc)
+        .all()
+        .await
+        .unwrap();
+    assert_eq!(keys(&tasks), ["d", "c", "b"]);
+
+    let tasks = objects
+        .filter(fields.tags.contains("bug").or(fields.points.gt(3)))
+        .order_by("points", Order::Asc)
+        .start(1)
+        .limit(2)
+        .all()
+        .await
+        .unwrap();
+    assert_eq!(keys(&tasks), ["b", "d"]);
+
+    let tasks = objects
+        .filter(fields.points.inside(vec![1, 3, 5]))
+        .filter(fields.title.ne("task a".to_string()))
+        .all()
+        .await
+        .unwrap();
+    assert_eq!(keys(&tasks), ["c"]);
+
+    let tasks = objects
+        .filter(fields.tags.contains("ui").not())
+        .order_by("id", Order::Asc)
+        .all()
+        .await
+        .unwrap();
+    assert_eq!(keys(&tasks), ["a", "d"]);
+
+    let first = objects
+        .query()
+        .order_by("points", Order::Desc)
+        .first()
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(first.id, Id::new("d"));
+    assert_eq!(
+        objects.filter(fields.points.lt(3)).count().await.unwrap(),
+        2
+    );
+    assert!(objects.filter(fields.points.eq(4)).exists().await.unwrap());
+    assert!(!objects.filter(fields.points.eq(5)).exists().await.unwrap());
+}
+
+#[tokio::test]
+pub async fn soft_delete() {
+    let db = setup().await;
+    let fields = Task::fields;
+    let objects = Task::objects(&db);
+
+    // Deletions return the record as it was before
+    let deleted = objects.delete(Id::<Task>::new("a")).await.unwrap().unwrap();
+    assert!(deleted.deleted_at.is_none());
+    assert_eq!(objects.delete_where(fields.points.gt(3)).await.unwrap(), 1);
+
+    assert_eq!(keys(&objects.all().await.unwrap()), ["b", "c"]);
+    assert!(
+        objects
+            .get_by_id(Id::<Task>::new("a"))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(objects.query().count().await.unwrap(), 2);
+    assert_eq!(objects.with_deleted().count().await.unwrap(), 4);
+    let trashed = objects
+        .query()
+        .only_deleted()
+        .order_by("id", Order::Asc)
+        .all()
+        .await
+        .unwrap();
+    assert_eq!(keys(&trashed), ["a", "d"]);
+    assert!(trashed.iter().all(|task| task.deleted_at.is_some()));
+
+    let restored = objects
+        .restore(Id::<Task>::new("a"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(restored.deleted_at.is_none());
+    // Records that are not deleted are left alone
+    assert!(
+        objects
+            .restore(Id::<Task>::new("b"))
+            .await
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(objects.query().count().await.unwrap(), 3);
+
+    objects.force_delete(Id::<Task>::new("d")).await.unwrap();
+    assert_eq!(objects.with_deleted().count().await.unwrap(), 3);
+    assert!(!objects.query().only_deleted().exists().await.unwrap());
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use merak_core::{Id, Model, registry};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[allow(dead_code)]
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "users")]
+struct User {
+    #[field(primary)]
+    id: Id<User>,
+    #[field(unique)]
+    email: String,
+    #[serde(rename = "displayName")]
+    display_name: Option<String>,
+    #[serde(skip)]
+    session: Option<String>,
+    #[field(created_at)]
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "posts", soft_delete)]
+struct Post {
+    #[field(primary)]
+    id: RecordId,
+    #[field(foreign_key = User, index)]
+    author: RecordId,
+    reviewers: Vec<Id<User>>,
+    #[field(updated_at)]
+    updated_at: DateTime<Utc>,
+    #[field(version)]
+    version: u64,
+    deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "likes", relation(in = User, out = Post))]
+struct Like {
+    #[field(primary)]
+    id: RecordId,
+    r#in: RecordId,
+    out: RecordId,
+}
+
+#[test]
+pub fn models() {
+    let tables = registry::models()
+        .iter()
+        .map(|model| model.table_name)
+        .collect::<Vec<_>>();
+    assert_eq!(tables, vec!["likes", "posts", "users"]);
+
+    let like = registry::model("likes").unwrap();
+    assert_eq!(like.name, "Like");
+    assert_eq!(like.relation, Some(("users", "posts")));
+    assert!(registry::model("comments").is_none());
+}
+
+#[test]
+pub fn fields() {
+    let user = registry::model("users").unwrap();
+    assert_eq!(user.primary_key, Some("id"));
+    let fields = user
+        .fields
+        .iter()
+        .map(|field| (field.name, field.ty))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        fields,
+        vec![
+            ("id", "Id<User>"),
+            ("email", "String"),
+            ("displayName", "Option<String>"),
+            ("created_at", "DateTime<Utc>"),
+        ]
+    );
+    assert!(user.field("id").unwrap().primary);
+    assert_eq!(
+        user.indexes().map(|field| field.name).collect::<Vec<_>>(),
+        vec!["email"]
+    );
+    assert_eq!(
+        user.created_at().map(|field| field.name),
+        Some("created_at")
+    );
+    assert!(user.updated_at().is_none());
+
+    let post = registry::model("posts").unwrap();
+    assert!(post.soft_delete);
+    let foreign_keys = post
+        .foreign_keys()
+        .map(|field| (field.name, field.foreign_key.unwrap()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        foreign_keys,
+        vec![("author", "users"), ("reviewers", "users")]
+    );
+    assert_eq!(
+        post.updated_at().map(|field| field.name),
+        Some("updated_at")
+    );
+    assert_eq!(post.version().map(|field| field.name), Some("version"));
+}
+
+#[test]
+pub fn schema() {
+    assert_eq!(
+        registry::model("posts").unwrap().table_schema(),
+        Post::schema()
+    );
+    let tables = registry::schema()
+        .tables()
+        .iter()
+        .map(|table| table.table)
+        .collect::<Vec<_>>();
+    assert_eq!(tables, vec!["likes", "posts", "users"]);
+}
+
+#[test]
+pub fn serialize() {
+    let user = serde_json::to_value(registry::model("users").unwrap()).unwrap();
+    assert_eq!(user["table_name"], "users");
+    assert_eq!(user["fields"][0]["type"], "Id<User>");
+    assert!(user.get("schema").is_none());
+}
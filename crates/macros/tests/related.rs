@@ -0,0 +1,130 @@
+use merak_core::{Id, Link};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "users")]
+struct User {
+    #[field(primary)]
+    id: RecordId,
+    name: String,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "tasks")]
+struct Task {
+    #[field(primary)]
+    id: RecordId,
+    #[field(foreign_key = User)]
+    owner_id: RecordId,
+    #[field(foreign_key = User, related_name = "assigned_tasks")]
+    assignee_id: RecordId,
+}
+
+#[test]
+pub fn foreign_key_links() {
+    let owner: Link<Task, User> = Task::fields.owner_id;
+    assert_eq!(owner.name(), "owner_id");
+
+    // Links can be used like plain fields of ids in conditions
+    let _ = Task::fields.owner_id.eq(Id::<User>::new("merak"));
+}
+
+/// Uses the accessors like a crate which does not define the models
+mod other {
+    use merak_core::schema::Schema;
+    use merak_core::testing::TestDb;
+    use merak_core::{Id, Model, Order};
+    use surrealdb::RecordId;
+
+    use super::{Task, TaskInput, TaskRelatedToUser, User, UserInput};
+
+    async fn user(db: &TestDb, name: &str) -> User {
+        let input = UserInput {
+            name: name.to_string(),
+        };
+        db.fixture_with_id(name, input).await.unwrap()
+    }
+
+    async fn setup() -> (TestDb, User, User) {
+        let db = TestDb::with_schema(Schema::new().register::<User>().register::<Task>())
+            .await
+            .unwrap();
+        let alice = user(&db, "alice").await;
+        let bob = user(&db, "bob").await;
+        for (key, owner, assignee) in [
+            ("a", &alice, &bob),
+            ("b", &alice, &alice),
+            ("c", &bob, &bob),
+        ] {
+            let input = TaskInput {
+                owner_id: owner.id.clone(),
+                assignee_id: assignee.id.clone(),
+            };
+            db.fixture_with_id::<Task>(key, input).await.unwrap();
+        }
+        (db, alice, bob)
+    }
+
+    #[tokio::test]
+    pub async fn has_many_accessors() {
+        let (db, alice, bob) = setup().await;
+
+        assert_eq!(alice.task_set(&db).count().await.unwrap(), 2);
+        assert_eq!(alice.assigned_tasks(&db).count().await.unwrap(), 1);
+        let tasks = bob.assigned_tasks(&db).all().await.unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert!(
+            tasks
+                .iter()
+                .all(|task| task.assignee_id == RecordId::from(("users", "bob")))
+        );
+    }
+
+    #[tokio::test]
+    pub async fn fetch_related() {
+        let (db, alice, _) = setup().await;
+        let fields = Task::fields;
+        let missing = RecordId::from(("users", "missing"));
+        let input = TaskInput {
+            owner_id: missing.clone(),
+            assignee_id: missing,
+        };
+        db.fixture_with_id::<Task>("d", input).await.unwrap();
+
+        let tasks = Task::objects(&db)
+            .filter(fields.assignee_id.eq(Id::<User>::new("bob")).not())
+            .order_by("id", Order::Asc)
+            .fetch(fields.owner_id)
+            .all()
+            .await
+            .unwrap();
+        let fetched: Vec<_> = tasks
+            .iter()
+            .map(|task| {
+                let owner = task.related.as_ref().map(|owner| owner.name.as_str());
+                (task.model.id.clone(), owner)
+            })
+            .collect();
+        // Links to missing records are not resolved
+        assert_eq!(
+            fetched,
+            [
+                (RecordId::from(("tasks", "b")), Some("alice")),
+                (RecordId::from(("tasks", "d")), None),
+            ]
+        );
+
+        let first = Task::objects(&db)
+            .filter(fields.owner_id.eq(Id::<User>::new("alice")))
+            .order_by("id", Order::Desc)
+            .fetch(fields.assignee_id)
+            .first()
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.model.id, RecordId::from(("tasks", "b")));
+        assert_eq!(first.related.unwrap().id, alice.id);
+    }
+}
@@ -0,0 +1,169 @@
+use merak_core::schema::Schema;
+use merak_core::testing::TestDb;
+use merak_core::{Id, Model, Relation};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "users")]
+struct User {
+    #[field(primary)]
+    id: RecordId,
+    name: String,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "projects")]
+struct Project {
+    #[field(primary)]
+    id: RecordId,
+    title: String,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "members", relation(in = User, out = Project))]
+struct Member {
+    #[field(primary)]
+    id: RecordId,
+    r#in: RecordId,
+    out: RecordId,
+    role: String,
+}
+
+#[test]
+pub fn relation_schema() {
+    assert_eq!(
+        Member::schema().statements(),
+        vec![
+            "DEFINE TABLE OVERWRITE members TYPE RELATION IN users OUT projects SCHEMAFULL",
+            "DEFINE FIELD OVERWRITE role ON TABLE members TYPE string",
+        ]
+    );
+}
+
+#[test]
+pub fn relation_endpoints() {
+    fn endpoints<E: Relation>() -> (&'static str, &'static str) {
+        (<E::In as Model>::TABLE_NAME, <E::Out as Model>::TABLE_NAME)
+    }
+
+    assert_eq!(endpoints::<Member>(), ("users", "projects"));
+    assert_eq!(Member::fields.r#in.name(), "in");
+    assert_eq!(Member::fields.out.name(), "out");
+
+    // Endpoints are set by `RELATE`, only edge properties are part of the input
+    let _ = MemberInput {
+        role: "owner".to_string(),
+    };
+}
+
+async fn setup() -> TestDb {
+    let schema = Schema::new()
+        .register::<User>()
+        .register::<Project>()
+        .register::<Member>();
+    let db = TestDb::with_schema(schema).await.unwrap();
+    for name in ["alice", "bob"] {
+        let input = UserInput {
+            name: name.to_string(),
+        };
+        db.fixture_with_id::<User>(name, input).await.unwrap();
+    }
+    for title in ["merak", "docs"] {
+        let input = ProjectInput {
+            title: title.to_string(),
+        };
+        db.fixture_with_id::<Project>(title, input).await.unwrap();
+    }
+    db
+}
+
+fn member(role: &str) -> MemberInput {
+    MemberInput {
+        role: role.to_string(),
+    }
+}
+
+#[tokio::test]
+pub async fn relate_and_traverse() {
+    let db = setup().await;
+    let alice = Id::<User>::new("alice");
+
+    let edge = Member::relate(&db, &alice, "merak", member("owner"))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(edge.r#in, RecordId::from(("users", "alice")));
+    assert_eq!(edge.out, RecordId::from(("projects", "merak")));
+    assert_eq!(edge.role, "owner");
+    Member::relate(&db, &alice, "docs", member("viewer"))
+        .await
+        .unwrap();
+    Member::relate(&db, "bob", "merak", member("viewer"))
+        .await
+        .unwrap();
+    // Relating twice creates a second edge, traversals return distinct records
+    Member::relate(&db, "bob", "merak", member("owner"))
+        .await
+        .unwrap();
+
+    let mut titles = Member::outgoing(&db, &alice)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|project| project.title)
+        .collect::<Vec<_>>();
+    titles.sort();
+    assert_eq!(titles, vec!["docs", "merak"]);
+    let mut names = Member::incoming(&db, "merak")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|user| user.name)
+        .collect::<Vec<_>>();
+    names.sort();
+    assert_eq!(names, vec!["alice", "bob"]);
+    let objects = Member::objects(&db);
+    assert_eq!(objects.edges_from("bob").count().await.unwrap(), 2);
+    assert_eq!(objects.edges_to("docs").count().await.unwrap(), 1);
+}
+
+#[tokio::test]
+pub async fn unrelate() {
+    let db = setup().await;
+    Member::relate(&db, "alice", "merak", member("owner"))
+        .await
+        .unwrap();
+    Member::relate(&db, "alice", "docs", member("owner"))
+        .await
+        .unwrap();
+    Member::relate(&db, "bob", "merak", member("viewer"))
+        .await
+        .unwrap();
+
+    let removed = Member::unrelate(&db, "alice", "merak").await.unwrap();
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].role, "owner");
+
+    let titles = Member::outgoing(&db, "alice")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|project| project.title)
+        .collect::<Vec<_>>();
+    assert_eq!(titles, vec!["docs"]);
+    let names = Member::incoming(&db, "merak")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|user| user.name)
+        .collect::<Vec<_>>();
+    assert_eq!(names, vec!["bob"]);
+    assert!(
+        Member::unrelate(&db, "alice", "merak")
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
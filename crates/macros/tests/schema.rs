@@ -0,0 +1,161 @@
+use chrono::{DateTime, Utc};
+use merak_core::Model;
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[test]
+pub fn field_types() {
+    #[derive(Model, Serialize, Deserialize)]
+    #[model(table_name = "any_table")]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        name: String,
+        age: u32,
+        score: Option<f64>,
+        tags: Vec<String>,
+        created_at: DateTime<Utc>,
+    }
+
+    assert_eq!(
+        AnyModel::schema().statements(),
+        vec![
+            "DEFINE TABLE OVERWRITE any_table SCHEMAFULL",
+            "DEFINE FIELD OVERWRITE name ON TABLE any_table TYPE string",
+            "DEFINE FIELD OVERWRITE age ON TABLE any_table TYPE int",
+            "DEFINE FIELD OVERWRITE score ON TABLE any_table TYPE option<float>",
+            "DEFINE FIELD OVERWRITE tags ON TABLE any_table TYPE array<string>",
+            "DEFINE FIELD OVERWRITE created_at ON TABLE any_table VALUE <datetime> $value",
+        ]
+    );
+}
+
+#[test]
+pub fn foreign_key_and_indexes() {
+    #[derive(Model, Serialize, Deserialize)]
+    #[model(table_name = "other_table")]
+    struct OtherModel {
+        #[field(primary)]
+        id: RecordId,
+        #[field(unique)]
+        email: String,
+    }
+
+    #[derive(Model, Serialize, Deserialize)]
+    #[model(table_name = "any_table")]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        #[field(foreign_key = OtherModel, index)]
+        owner: RecordId,
+        link: RecordId,
+    }
+
+    assert_eq!(
+        OtherModel::schema().statements(),
+        vec![
+            "DEFINE TABLE OVERWRITE other_table SCHEMAFULL",
+            "DEFINE FIELD OVERWRITE email ON TABLE other_table TYPE string",
+            "DEFINE INDEX OVERWRITE other_table_email_unique ON TABLE other_table FIELDS email UNIQUE",
+        ]
+    );
+    assert_eq!(
+        AnyModel::schema().statements(),
+        vec![
+            "DEFINE TABLE OVERWRITE any_table SCHEMAFULL",
+            "DEFINE FIELD OVERWRITE owner ON TABLE any_table TYPE record<other_table>",
+            "DEFINE FIELD OVERWRITE link ON TABLE any_table TYPE record",
+            "DEFINE INDEX OVERWRITE any_table_owner_index ON TABLE any_table FIELDS owner",
+        ]
+    );
+}
+
+#[test]
+pub fn timestamps() {
+    #[derive(Model, Serialize, Deserialize)]
+    #[model(table_name = "any_table")]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        name: String,
+        #[field(created_at)]
+        created_at: DateTime<Utc>,
+        #[field(updated_at)]
+        updated_at: DateTime<Utc>,
+    }
+
+    assert_eq!(
+        AnyModel::schema().statements(),
+        vec![
+            "DEFINE TABLE OVERWRITE any_table SCHEMAFULL",
+            "DEFINE FIELD OVERWRITE name ON TABLE any_table TYPE string",
+            "DEFINE FIELD OVERWRITE created_at ON TABLE any_table VALUE <datetime> ($before OR time::now())",
+            "DEFINE FIELD OVERWRITE updated_at ON TABLE any_table VALUE <datetime> (time::now())",
+        ]
+    );
+
+    // Timestamps are filled by the database, not by the input
+    let _ = AnyModelInput {
+        name: "merak".to_string(),
+    };
+}
+
+#[test]
+pub fn version() {
+    #[derive(Model, Serialize, Deserialize)]
+    #[model(table_name = "any_table")]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        name: String,
+        #[field(version)]
+        version: u64,
+    }
+
+    assert_eq!(AnyModel::VERSION_FIELD, Some("version"));
+    assert_eq!(
+        AnyModel::schema().statements(),
+        vec![
+            "DEFINE TABLE OVERWRITE any_table SCHEMAFULL",
+            "DEFINE FIELD OVERWRITE name ON TABLE any_table TYPE string",
+            "DEFINE FIELD OVERWRITE version ON TABLE any_table VALUE <int> (($before OR 0) + 1)",
+        ]
+    );
+
+    // The version read with the record is sent back to guard the update,
+    // inputs without one are written unconditionally
+    let input: AnyModelInput = serde_json::from_str(r#"{"name":"merak"}"#).unwrap();
+    assert_eq!(input.version, None);
+    assert_eq!(
+        serde_json::to_string(&input).unwrap(),
+        r#"{"name":"merak"}"#
+    );
+}
+
+#[test]
+pub fn soft_delete() {
+    #[derive(Model, Serialize, Deserialize)]
+    #[model(table_name = "any_table", soft_delete)]
+    struct AnyModel {
+        #[field(primary)]
+        id: RecordId,
+        name: String,
+        deleted_at: Option<DateTime<Utc>>,
+    }
+
+    const { assert!(AnyModel::SOFT_DELETE) };
+    assert_eq!(
+        AnyModel::schema().statements(),
+        vec![
+            "DEFINE TABLE OVERWRITE any_table SCHEMAFULL",
+            "DEFINE FIELD OVERWRITE name ON TABLE any_table TYPE string",
+            "DEFINE FIELD OVERWRITE deleted_at ON TABLE any_table VALUE <option<datetime>> $value",
+        ]
+    );
+
+    // `deleted_at` is only written by `delete` and `restore`
+    let _ = AnyModelInput {
+        name: "merak".to_string(),
+    };
+}
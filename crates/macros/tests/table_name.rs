@@ -1,3 +1,4 @@
+use merak_core::Model;
 use merak_macros::Model;
 use serde::{Deserialize, Serialize};
 use surrealdb::RecordId;
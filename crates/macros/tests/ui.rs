@@ -0,0 +1,9 @@
+// The compiled cases depend on `merak-core` without its `utoipa` feature, so
+// with the feature enabled here every expanded model also reports the missing
+// `Model::Data` and the expected output differs
+#[cfg(not(feature = "utoipa"))]
+#[test]
+pub fn ui() {
+    let cases = trybuild::TestCases::new();
+    cases.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,16 @@
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table = "users")]
+struct User {
+    #[field(primary)]
+    id: RecordId,
+    #[field(uniq)]
+    username: String,
+    #[field(updated_at)]
+    updated_at: String,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[derive(Model, Serialize, Deserialize)]
+struct User {
+    #[field(primary, created_at)]
+    id: RecordId,
+    created_at: DateTime<Utc>,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[derive(Model, Serialize, Deserialize)]
+struct User {
+    #[field(primary)]
+    id: RecordId,
+    #[field(primary)]
+    uuid: RecordId,
+}
+
+fn main() {}
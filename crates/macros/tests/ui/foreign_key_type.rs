@@ -0,0 +1,28 @@
+use merak_core::Id;
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[derive(Model, Serialize, Deserialize)]
+struct User {
+    #[field(primary)]
+    id: RecordId,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+struct Project {
+    #[field(primary)]
+    id: RecordId,
+}
+
+#[derive(Model, Serialize, Deserialize)]
+struct Post {
+    #[field(primary)]
+    id: RecordId,
+    #[field(foreign_key = User)]
+    author_id: String,
+    #[field(foreign_key = User)]
+    project_id: Id<Project>,
+}
+
+fn main() {}
@@ -0,0 +1,21 @@
+use merak_core::transaction::Transaction;
+use merak_core::{ModelHooks, SurrealClient};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(hooks)]
+struct User {
+    #[field(primary)]
+    id: RecordId,
+    name: String,
+}
+
+impl ModelHooks for User {}
+
+fn main() {
+    let client = SurrealClient::init();
+    let mut tx = Transaction::new(&client);
+    tx.objects::<User>();
+}
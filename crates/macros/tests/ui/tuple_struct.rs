@@ -0,0 +1,8 @@
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[derive(Model, Serialize, Deserialize)]
+struct User(RecordId, String);
+
+fn main() {}
@@ -0,0 +1,13 @@
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+#[derive(Model, Serialize, Deserialize)]
+struct Post {
+    #[field(primary)]
+    id: RecordId,
+    #[field(foreign_key = Author)]
+    author_id: RecordId,
+}
+
+fn main() {}
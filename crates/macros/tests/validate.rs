@@ -0,0 +1,96 @@
+use merak_core::Validate;
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+use surrealdb::RecordId;
+
+fn no_spaces(value: &str) -> Result<(), String> {
+    if value.contains(' ') {
+        Err("Must not contain spaces".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "any_table")]
+struct AnyModel {
+    #[field(primary)]
+    id: RecordId,
+    #[field(validate(length(min = 3, max = 50), regex = "^[a-z0-9_ ]+$", custom = no_spaces))]
+    username: String,
+    #[field(validate(email))]
+    email: Option<String>,
+    #[field(validate(range(min = 13, max = 150)))]
+    age: u32,
+}
+
+fn input() -> AnyModelInput {
+    AnyModelInput {
+        username: "merak".to_string(),
+        email: Some("merak@example.com".to_string()),
+        age: 20,
+    }
+}
+
+#[test]
+pub fn valid_input() {
+    assert!(input().validate().is_ok());
+    assert!(AnyModelPatch::default().validate().is_ok());
+}
+
+#[test]
+pub fn field_errors() {
+    let input = AnyModelInput {
+        username: "Me rak".to_string(),
+        email: Some("merak".to_string()),
+        age: 7,
+    };
+    let errors = input.validate().unwrap_err();
+    let errors = errors
+        .errors()
+        .iter()
+        .map(|error| (error.field, error.code))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        errors,
+        vec![
+            ("username", "regex"),
+            ("username", "custom"),
+            ("email", "email"),
+            ("age", "range"),
+        ]
+    );
+
+    let patch = AnyModelPatch {
+        username: Some("me".to_string()),
+        ..Default::default()
+    };
+    let errors = patch.validate().unwrap_err();
+    assert_eq!(
+        errors.to_string(),
+        "username: Length must be between 3 and 50"
+    );
+}
+
+#[test]
+pub fn model() {
+    let model = AnyModel {
+        id: RecordId::from(("any_table", "merak")),
+        username: "merak".to_string(),
+        email: None,
+        age: 200,
+    };
+    assert_eq!(
+        model.validate().unwrap_err().to_string(),
+        "age: Value must be between 13 and 150"
+    );
+}
+
+#[test]
+pub fn optional_fields() {
+    let input = AnyModelInput {
+        email: None,
+        ..input()
+    };
+    assert!(input.validate().is_ok());
+}
@@ -0,0 +1,108 @@
+use merak_core::schema::Schema;
+use merak_core::testing::TestDb;
+use merak_core::transaction::Transaction;
+use merak_core::{Error, Id, Model};
+use merak_macros::Model;
+use serde::{Deserialize, Serialize};
+
+#[derive(Model, Serialize, Deserialize)]
+#[model(table_name = "documents")]
+struct Document {
+    #[field(primary)]
+    id: Id<Document>,
+    title: String,
+    #[field(version)]
+    version: u64,
+}
+
+async fn setup() -> (TestDb, Document) {
+    let db = TestDb::with_schema(Schema::new().register::<Document>())
+        .await
+        .unwrap();
+    let input = DocumentInput {
+        title: "draft".to_string(),
+        version: None,
+    };
+    let document: Document = db.fixture_with_id("merak", input).await.unwrap();
+    (db, document)
+}
+
+fn input(title: &str, version: Option<u64>) -> DocumentInput {
+    DocumentInput {
+        title: title.to_string(),
+        version,
+    }
+}
+
+#[tokio::test]
+pub async fn update_checks_version() {
+    let (db, document) = setup().await;
+    assert_eq!(document.version, 1);
+    let objects = Document::objects(&db);
+
+    let updated = objects
+        .update(&document.id, input("first", Some(1)))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((updated.title.as_str(), updated.version), ("first", 2));
+
+    let stale = objects.update(&document.id, input("stale", Some(1))).await;
+    assert!(matches!(stale, Err(Error::Conflict(_))));
+
+    // Without a version the write is not checked
+    let updated = objects
+        .update(&document.id, input("forced", None))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((updated.title.as_str(), updated.version), ("forced", 3));
+
+    // Missing records are not conflicts
+    let missing = objects.update("missing", input("missing", Some(1))).await;
+    assert!(matches!(missing, Ok(None)));
+}
+
+#[tokio::test]
+pub async fn concurrent_saves() {
+    let (db, document) = setup().await;
+    let mut first = Document::get_by_id(&db, &document.id)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut second = Document::get_by_id(&db, &document.id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    first.title = "first".to_string();
+    let saved = first.save(&db).await.unwrap().unwrap();
+    assert_eq!(saved.version, 2);
+
+    second.title = "second".to_string();
+    assert!(matches!(second.save(&db).await, Err(Error::Conflict(_))));
+
+    let stored = Document::get_by_id(&db, &document.id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!((stored.title.as_str(), stored.version), ("first", 2));
+}
+
+#[tokio::test]
+pub async fn transaction_checks_version() {
+    let (db, document) = setup().await;
+
+    let mut tx = Transaction::new(&db);
+    let _ = tx
+        .objects::<Document>()
+        .update(&document.id, input("stale", Some(7)));
+    assert!(matches!(tx.commit().await, Err(Error::Conflict(_))));
+
+    let mut tx = Transaction::new(&db);
+    let updated = tx
+        .objects::<Document>()
+        .update(&document.id, input("matching", Some(1)));
+    let updated = tx.commit().await.unwrap().take(updated).unwrap().unwrap();
+    assert_eq!((updated.title.as_str(), updated.version), ("matching", 2));
+}
@@ -65,4 +65,7 @@ pub mod auth {
 
     /// Unauthorized (missing or invalid authorization header)
     pub const UNAUTHORIZED: i32 = make_code(category::BUSINESS_ERROR, module::AUTH, 8);
+
+    /// Request fields failed validation (username length, email format)
+    pub const INVALID_INPUT: i32 = make_code(category::BUSINESS_ERROR, module::AUTH, 9);
 }
@@ -1,4 +1,5 @@
 use chrono::Utc;
+use merak_core::Page;
 use serde::Serialize;
 use utoipa::{ToResponse, ToSchema};
 
@@ -60,3 +61,36 @@ impl ApiResponse<EmptyData> {
         Self::new(code, message, EmptyData::default())
     }
 }
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(bound = "T: ToSchema")]
+pub struct PaginatedResponse<T> {
+    /// Business result code for 2xx responses (CMMRR or 0).
+    pub code: i32,
+    /// Message describing the result.
+    pub message: String,
+    /// Server timestamp in milliseconds.
+    pub timestamp: i64,
+    /// Items of the current page.
+    pub items: Vec<T>,
+    /// Cursor of the next page, absent on the last page.
+    pub next_cursor: Option<String>,
+    /// Whether more items follow this page.
+    pub has_more: bool,
+    /// Number of matching items, when requested.
+    pub total: Option<u64>,
+}
+
+impl<T> PaginatedResponse<T> {
+    pub fn ok(page: Page<T>) -> Self {
+        Self {
+            code: CODE_OK,
+            message: "OK".to_string(),
+            timestamp: Utc::now().timestamp_millis(),
+            items: page.items,
+            next_cursor: page.next_cursor,
+            has_more: page.has_more,
+            total: page.total,
+        }
+    }
+}
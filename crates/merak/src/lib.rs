@@ -1,4 +1,5 @@
 pub mod common;
+pub mod migrations;
 pub mod models;
 pub mod routes;
 pub mod services;
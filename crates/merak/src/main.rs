@@ -3,6 +3,7 @@ use std::{env, sync::Arc};
 
 use axum::http::StatusCode;
 use axum::routing::get;
+use merak_core::Fixtures;
 use serde::Serialize;
 use surrealdb::opt::auth::Root;
 use utoipa::{OpenApi, ToSchema};
@@ -11,6 +12,8 @@ use utoipa_redoc::{Redoc, Servable};
 
 use merak::common::code;
 use merak::common::response::{ApiResponse, ErrorResponse};
+use merak::migrations;
+use merak::models;
 use merak::routes::auth;
 use merak::services::auth::AuthService;
 
@@ -70,6 +73,24 @@ async fn main() -> anyhow::Result<()> {
     let db = surrealdb::engine::any::connect(&url).await?;
     db.use_ns(&ns).use_db(&db_name).await?;
     db.signin(creds).await?;
+
+    // Define tables, fields and indexes for all models and run pending
+    // migrations under the migration lock, then report schema drift
+    let schema = models::schema();
+    let migrator = migrations::migrator().schema(schema.clone());
+    for version in migrator.up(&db).await? {
+        println!("Applied migration {}", version);
+    }
+    for drift in migrator.drift(&db, &schema).await? {
+        println!("Schema drift: {}", drift);
+    }
+
+    // Load seed data for QA and demo environments
+    if let Ok(path) = env::var("SEED_FILE") {
+        let fixtures = Fixtures::from_path(&path)?;
+        let seeded = models::seeder().load(&db, &fixtures).await?;
+        println!("Seeded {} records from {}", seeded.len(), path);
+    }
     let state = Arc::new(db);
 
     // Create auth state
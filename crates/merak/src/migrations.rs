@@ -0,0 +1,9 @@
+use merak_core::migration::Migrator;
+
+/// Migrations applied on startup
+///
+/// Register new migrations here with increasing versions, e.g.
+/// `.register(Migration::sql(1, "create_projects", include_str!("../migrations/0001_create_projects.surql")))`.
+pub fn migrator() -> Migrator {
+    Migrator::new()
+}
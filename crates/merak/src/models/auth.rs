@@ -1,29 +1,41 @@
 use chrono::{DateTime, Utc};
-use merak_macros::Model;
+use merak_core::Id;
+use merak_macros::{Factory, Model};
 use serde::{Deserialize, Serialize};
-use surrealdb::RecordId;
 
 #[derive(Model, Serialize, Deserialize)]
 #[model(table_name = "auth_sessions")]
 pub struct AuthSession {
     #[field(primary)]
-    pub id: RecordId,
-    #[field(foreign_key = User)]
-    pub user_id: RecordId,
+    pub id: Id<AuthSession>,
+    #[field(foreign_key = User, index)]
+    pub user_id: Id<User>,
     pub refresh_jti: String,
     pub refresh_expires_at: DateTime<Utc>,
+    #[field(created_at)]
     pub created_at: DateTime<Utc>,
     pub last_used_at: DateTime<Utc>,
 }
 
-#[derive(Model, Serialize, Deserialize)]
+/// Argon2 hash of `Password123`, the password of users built by the factory
+pub const FACTORY_PASSWORD_HASH: &str = "$argon2id$v=19$m=65536,t=3,p=4$zcSBn6tkYGlZmly8TV57dw$9+EBnKngTYjxrDO2tBeMmvdgwQL/ILk30numh7r8wBE";
+
+#[derive(Model, Factory, Serialize, Deserialize)]
 #[model(table_name = "users")]
 pub struct User {
     #[field(primary)]
-    pub id: RecordId,
+    pub id: Id<User>,
+    #[field(unique, validate(length(min = 3, max = 50)))]
+    #[factory(sequence = "user{n}")]
     pub username: String,
+    #[field(unique, validate(email))]
+    #[factory(sequence = "user{n}@example.com")]
     pub email: String,
+    #[field(skip_data)]
+    #[factory(default = FACTORY_PASSWORD_HASH.to_string())]
     pub password_hash: String,
+    #[field(created_at)]
     pub created_at: DateTime<Utc>,
+    #[field(updated_at)]
     pub updated_at: DateTime<Utc>,
 }
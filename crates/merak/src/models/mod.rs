@@ -1 +1,17 @@
+use merak_core::Seeder;
+use merak_core::registry;
+use merak_core::schema::Schema;
+
 pub mod auth;
+
+/// Tables, fields and indexes of all models, as registered by their derive
+pub fn schema() -> Schema {
+    registry::schema()
+}
+
+/// Loads fixtures of all models, users missing fields are built by their factory
+pub fn seeder() -> Seeder {
+    Seeder::new()
+        .register_factory::<auth::User>()
+        .register::<auth::AuthSession>()
+}
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{FromRequestParts, Json, State},
+    extract::{FromRequestParts, Json, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
 };
@@ -9,13 +9,13 @@ use axum_extra::{
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use utoipa::{ToResponse, ToSchema};
+use utoipa::{IntoParams, ToResponse, ToSchema};
 use utoipa_axum::{router::OpenApiRouter, routes};
 
 use merak_core::SurrealClient;
 
 use crate::common::code;
-use crate::common::response::{ApiResponse, CODE_OK, EmptyData, ErrorResponse};
+use crate::common::response::{ApiResponse, CODE_OK, EmptyData, ErrorResponse, PaginatedResponse};
 use crate::services::{auth::AuthService, jwt::TokenPair};
 
 /// Authentication route state
@@ -108,6 +108,41 @@ impl From<crate::models::auth::User> for UserResponse {
     }
 }
 
+/// Session response
+#[derive(Debug, Serialize, ToSchema, ToResponse)]
+pub struct SessionResponse {
+    /// Session ID
+    pub id: String,
+    /// Creation timestamp
+    pub created_at: String,
+    /// Last refresh timestamp
+    pub last_used_at: String,
+    /// Refresh token expiration timestamp
+    pub expires_at: String,
+}
+
+impl From<crate::models::auth::AuthSession> for SessionResponse {
+    fn from(session: crate::models::auth::AuthSession) -> Self {
+        Self {
+            id: session.id.to_string(),
+            created_at: session.created_at.to_rfc3339(),
+            last_used_at: session.last_used_at.to_rfc3339(),
+            expires_at: session.refresh_expires_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Pagination query
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageQuery {
+    /// Cursor returned with the previous page
+    pub cursor: Option<String>,
+    /// Page size (1-100, default 20)
+    #[param(minimum = 1, maximum = 100)]
+    pub limit: Option<u64>,
+}
+
 /// Registration response
 #[derive(Debug, Serialize, ToSchema, ToResponse)]
 pub struct RegisterResponse {
@@ -334,6 +369,49 @@ pub async fn get_me(State(state): State<AuthState>, BearerToken(bearer): BearerT
     }
 }
 
+/// List sessions
+///
+/// List the sessions of the current user, most recently used first
+#[utoipa::path(
+    get,
+    path = "/sessions",
+    params(PageQuery),
+    responses(
+        (status = 200, description = "Successfully retrieved sessions", body = PaginatedResponse<SessionResponse>),
+        (status = 400, description = "Invalid pagination cursor", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    security(
+        ("bearer_auth" = [])
+    ),
+    tag = "Authentication"
+)]
+pub async fn list_sessions(
+    State(state): State<AuthState>,
+    BearerToken(bearer): BearerToken,
+    Query(query): Query<PageQuery>,
+) -> Response {
+    let auth_service = state.auth_service.as_ref();
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    match auth_service
+        .list_sessions(&state.db, bearer.token(), limit, query.cursor.as_deref())
+        .await
+    {
+        Ok(page) => (
+            StatusCode::OK,
+            Json(PaginatedResponse::ok(page.map(SessionResponse::from))),
+        )
+            .into_response(),
+        Err(e) => {
+            let message = e.to_string();
+            let code = e.code();
+            (StatusCode::OK, Json(ErrorResponse::new(code, message))).into_response()
+        }
+    }
+}
+
 /// Create authentication routes
 pub fn routes() -> OpenApiRouter<AuthState> {
     OpenApiRouter::new()
@@ -342,6 +420,7 @@ pub fn routes() -> OpenApiRouter<AuthState> {
         .routes(routes!(refresh_token))
         .routes(routes!(logout))
         .routes(routes!(get_me))
+        .routes(routes!(list_sessions))
 }
 
 // pub struct AuthApiDoc;
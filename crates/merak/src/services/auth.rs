@@ -1,7 +1,6 @@
 use anyhow::anyhow;
-use chrono::Utc;
-use merak_core::{Model, SurrealClient};
-use surrealdb::RecordId;
+use merak_core::{Error, Id, Model, Page, SurrealClient, Transaction};
+use uuid::Uuid;
 
 use super::{
     error::{AuthError, AuthResult},
@@ -9,7 +8,7 @@ use super::{
     password::PasswordService,
     session::SessionService,
 };
-use crate::models::auth::{User, UserInput};
+use crate::models::auth::{AuthSession, User, UserInput, UserPatch};
 
 /// Authentication service for user registration, login, and token management
 pub struct AuthService {
@@ -68,49 +67,36 @@ impl AuthService {
             return Err(AuthError::WeakPassword);
         }
 
-        // Check if username already exists
-        let existing_by_username: Option<User> = db
-            .query("SELECT * FROM type::table($table) WHERE username = $username")
-            .bind(("table", User::TABLE_NAME))
-            .bind(("username", username.clone()))
-            .await?
-            .take(0)?;
-        if existing_by_username.is_some() {
-            return Err(AuthError::UsernameExists);
-        }
-
-        // Check if email already exists
-        let existing_by_email: Option<User> = db
-            .query("SELECT * FROM type::table($table) WHERE email = $email")
-            .bind(("table", User::TABLE_NAME))
-            .bind(("email", email.clone()))
-            .await?
-            .take(0)?;
-        if existing_by_email.is_some() {
-            return Err(AuthError::EmailExists);
-        }
-
         // Hash the password
         let password_hash = self.password_service.hash_password(&password)?;
 
-        // Create user
-        let now = Utc::now();
+        // Create user and its first session atomically, uniqueness of username
+        // and email is enforced by the unique indexes of the users table
+        let user_key = Uuid::new_v4().to_string();
+        let user_id = Id::<User>::new(user_key.clone());
         let user_input = UserInput {
             username,
             email,
             password_hash,
-            created_at: now,
-            updated_at: now,
         };
 
-        let created = User::objects(db).create(user_input).await?;
+        let mut tx = Transaction::new(db);
+        let created = tx.objects::<User>().create_with_id(user_key, user_input);
+        let session = self.session_service.create_session_tx(
+            &mut tx,
+            &user_id,
+            self.jwt_service.refresh_exp_seconds(),
+        );
+        let mut committed = tx.commit().await.map_err(|err| match err {
+            Error::UniqueViolation { field } if field == "username" => AuthError::UsernameExists,
+            Error::UniqueViolation { field } if field == "email" => AuthError::EmailExists,
+            err => err.into(),
+        })?;
+
+        let user = committed
+            .take(created)?
+            .ok_or_else(|| AuthError::Internal(anyhow!("Failed to create user")))?;
 
-        let user = created.ok_or_else(|| AuthError::Internal(anyhow!("Failed to create user")))?;
-
-        let session = self
-            .session_service
-            .create_session(db, &user.id, self.jwt_service.refresh_exp_seconds())
-            .await?;
         let token_pair = self.jwt_service.generate_token_pair(
             &user.id.to_string(),
             &user.username,
@@ -138,12 +124,15 @@ impl AuthService {
         password: String,
     ) -> AuthResult<(User, TokenPair)> {
         // Find user (by username or email)
-        let user: Option<User> = db
-            .query("SELECT * FROM type::table($table) WHERE username = $identifier OR email = $identifier")
-            .bind(("table", User::TABLE_NAME))
-            .bind(("identifier", identifier.clone()))
-            .await?
-            .take(0)?;
+        let user = User::objects(db)
+            .filter(
+                User::fields
+                    .username
+                    .eq(identifier.clone())
+                    .or(User::fields.email.eq(identifier)),
+            )
+            .first()
+            .await?;
 
         let user = user.ok_or(AuthError::InvalidCredentials)?;
 
@@ -274,6 +263,33 @@ impl AuthService {
         self.session_service.delete_session(db, &claims.sid).await
     }
 
+    /// List the sessions of the current user
+    ///
+    /// # Arguments
+    /// - `db`: Database client
+    /// - `access_token`: Access token
+    /// - `limit`: Page size
+    /// - `cursor`: Cursor of the previous page
+    ///
+    /// # Returns
+    /// One page of sessions
+    pub async fn list_sessions(
+        &self,
+        db: &SurrealClient,
+        access_token: &str,
+        limit: u64,
+        cursor: Option<&str>,
+    ) -> AuthResult<Page<AuthSession>> {
+        let claims = self.verify_access_token(db, access_token).await?;
+        let user_id: Id<User> = claims
+            .sub
+            .parse()
+            .map_err(|e| AuthError::Internal(anyhow!("Failed to parse user id: {}", e)))?;
+        self.session_service
+            .list_sessions(db, &user_id, limit, cursor)
+            .await
+    }
+
     /// Get user information
     ///
     /// # Arguments
@@ -283,10 +299,10 @@ impl AuthService {
     /// # Returns
     /// User information
     pub async fn get_user(&self, db: &SurrealClient, user_id: &str) -> AuthResult<User> {
-        let record_id: RecordId = user_id
+        let user_id: Id<User> = user_id
             .parse()
             .map_err(|e| AuthError::Internal(anyhow!("Failed to parse user id: {}", e)))?;
-        let user = User::get_by_id(db, &record_id.key().to_string()).await?;
+        let user = User::get_by_id(db, &user_id).await?;
         user.ok_or(AuthError::UserNotFound)
     }
 
@@ -313,7 +329,7 @@ impl AuthService {
         }
 
         // Get the user
-        let mut user = self.get_user(db, user_id).await?;
+        let user = self.get_user(db, user_id).await?;
 
         // Verify old password
         let is_valid = self
@@ -327,13 +343,28 @@ impl AuthService {
         // Hash the new password
         let new_password_hash = self.password_service.hash_password(&new_password)?;
 
-        // Update password
-        user.password_hash = new_password_hash;
-        user.updated_at = Utc::now();
-
-        let updated = user.save(db).await?;
-
-        Ok(updated)
+        // Update only the password, rejecting the update if it changed since it was verified
+        let mut tx = Transaction::new(db);
+        tx.ensure::<User>(
+            User::fields
+                .id
+                .eq(user.id.clone())
+                .and(User::fields.password_hash.eq(user.password_hash)),
+            "password changed",
+        );
+        let updated = tx.merge::<User>(
+            user.id,
+            UserPatch {
+                password_hash: Some(new_password_hash),
+                ..Default::default()
+            },
+        );
+        let mut committed = tx.commit().await.map_err(|err| match err {
+            Error::Conflict(_) => AuthError::InvalidOldPassword,
+            err => err.into(),
+        })?;
+
+        Ok(committed.take(updated)?)
     }
 }
 
@@ -17,6 +17,7 @@ pub enum AuthError {
     SessionInvalid(String),
     UserNotFound,
     InvalidOldPassword,
+    InvalidInput(String),
     Internal(AnyError),
 }
 
@@ -34,6 +35,7 @@ impl AuthError {
             AuthError::TokenInvalid(_) | AuthError::TokenRevoked => code::auth::TOKEN_INVALID,
             AuthError::SessionInvalid(_) => code::auth::SESSION_INVALID,
             AuthError::UserNotFound => code::auth::USER_NOT_FOUND,
+            AuthError::InvalidInput(_) => code::auth::INVALID_INPUT,
             AuthError::Internal(_) => {
                 code::make_code(code::category::UNKNOWN_ERROR, code::module::AUTH, 99)
             }
@@ -70,6 +72,7 @@ impl fmt::Display for AuthError {
             }
             AuthError::UserNotFound => write!(f, "User not found"),
             AuthError::InvalidOldPassword => write!(f, "Invalid old password"),
+            AuthError::InvalidInput(reason) => write!(f, "{}", reason),
             AuthError::Internal(err) => write!(f, "{}", err),
         }
     }
@@ -95,3 +98,12 @@ impl From<surrealdb::Error> for AuthError {
         AuthError::Internal(err.into())
     }
 }
+
+impl From<merak_core::Error> for AuthError {
+    fn from(err: merak_core::Error) -> Self {
+        match err {
+            merak_core::Error::Invalid(errors) => AuthError::InvalidInput(errors.to_string()),
+            err => AuthError::Internal(err.into()),
+        }
+    }
+}
@@ -1,11 +1,10 @@
 use anyhow::anyhow;
 use chrono::{Duration, Utc};
-use merak_core::{Model, SurrealClient};
-use surrealdb::RecordId;
+use merak_core::{Error, Id, Model, Order, Page, SurrealClient, Transaction};
 use uuid::Uuid;
 
 use super::error::{AuthError, AuthResult};
-use crate::models::auth::{AuthSession, AuthSessionInput};
+use crate::models::auth::{AuthSession, AuthSessionInput, AuthSessionPatch, User};
 
 #[derive(Debug, Clone)]
 pub struct SessionInfo {
@@ -20,43 +19,62 @@ impl SessionService {
         Self
     }
 
-    pub async fn create_session(
-        &self,
-        db: &SurrealClient,
-        user_id: &RecordId,
+    fn new_session(
+        user_id: &Id<User>,
         refresh_exp_seconds: i64,
-    ) -> AuthResult<SessionInfo> {
+    ) -> (SessionInfo, AuthSessionInput) {
         let now = Utc::now();
-        let session_id = Uuid::new_v4().to_string();
-        let refresh_jti = Uuid::new_v4().to_string();
-        let refresh_expires_at = now + Duration::seconds(refresh_exp_seconds);
+        let session = SessionInfo {
+            session_id: Uuid::new_v4().to_string(),
+            refresh_jti: Uuid::new_v4().to_string(),
+        };
         let session_input = AuthSessionInput {
             user_id: user_id.clone(),
-            refresh_jti: refresh_jti.clone(),
-            refresh_expires_at,
-            created_at: now,
+            refresh_jti: session.refresh_jti.clone(),
+            refresh_expires_at: now + Duration::seconds(refresh_exp_seconds),
             last_used_at: now,
         };
+        (session, session_input)
+    }
+
+    pub async fn create_session(
+        &self,
+        db: &SurrealClient,
+        user_id: &Id<User>,
+        refresh_exp_seconds: i64,
+    ) -> AuthResult<SessionInfo> {
+        let (session, session_input) = Self::new_session(user_id, refresh_exp_seconds);
         let created = AuthSession::objects(db)
-            .create_with_id(session_id.clone(), session_input)
+            .create_with_id(session.session_id.clone(), session_input)
             .await?;
         created.ok_or_else(|| AuthError::Internal(anyhow!("Failed to create session")))?;
-        Ok(SessionInfo {
-            session_id,
-            refresh_jti,
-        })
+        Ok(session)
+    }
+
+    /// Queue the creation of a session in `tx`
+    pub fn create_session_tx(
+        &self,
+        tx: &mut Transaction<'_>,
+        user_id: &Id<User>,
+        refresh_exp_seconds: i64,
+    ) -> SessionInfo {
+        let (session, session_input) = Self::new_session(user_id, refresh_exp_seconds);
+        let _ = tx
+            .objects::<AuthSession>()
+            .create_with_id(session.session_id.clone(), session_input);
+        session
     }
 
     pub async fn cleanup_expired_for_user(
         &self,
         db: &SurrealClient,
-        user_id: &RecordId,
+        user_id: &Id<User>,
     ) -> AuthResult<()> {
-        let now = Utc::now();
-        db.query("DELETE FROM type::table($table) WHERE user_id = $user_id AND refresh_expires_at < $now")
-            .bind(("table", AuthSession::TABLE_NAME))
-            .bind(("user_id", user_id.clone()))
-            .bind(("now", now))
+        let fields = AuthSession::fields;
+        AuthSession::objects(db)
+            .filter(fields.user_id.eq(user_id.clone()))
+            .filter(fields.refresh_expires_at.lt(Utc::now()))
+            .delete()
             .await?;
         Ok(())
     }
@@ -76,18 +94,60 @@ impl SessionService {
         Ok(session)
     }
 
+    /// List the sessions of a user, most recently used first
+    pub async fn list_sessions(
+        &self,
+        db: &SurrealClient,
+        user_id: &Id<User>,
+        limit: u64,
+        cursor: Option<&str>,
+    ) -> AuthResult<Page<AuthSession>> {
+        let fields = AuthSession::fields;
+        let page = AuthSession::objects(db)
+            .filter(fields.user_id.eq(user_id.clone()))
+            .paginate(limit)
+            .order_by(fields.last_used_at, Order::Desc)
+            .after(cursor)
+            .fetch()
+            .await
+            .map_err(|err| match err {
+                Error::Validation(message) => AuthError::InvalidInput(message),
+                err => err.into(),
+            })?;
+        Ok(page)
+    }
+
     pub async fn rotate_refresh_jti(
         &self,
         db: &SurrealClient,
-        mut session: AuthSession,
+        session: AuthSession,
         refresh_exp_seconds: i64,
     ) -> AuthResult<String> {
         let now = Utc::now();
         let new_refresh_jti = Uuid::new_v4().to_string();
-        session.refresh_jti = new_refresh_jti.clone();
-        session.refresh_expires_at = now + Duration::seconds(refresh_exp_seconds);
-        session.last_used_at = now;
-        let _ = session.save(db).await?;
+        let fields = AuthSession::fields;
+        let mut tx = Transaction::new(db);
+        // Only one refresh may consume the current refresh token
+        tx.ensure::<AuthSession>(
+            fields
+                .id
+                .eq(session.id.clone())
+                .and(fields.refresh_jti.eq(session.refresh_jti.clone())),
+            "Refresh token already used",
+        );
+        let _ = tx.merge::<AuthSession>(
+            session.id,
+            AuthSessionPatch {
+                refresh_jti: Some(new_refresh_jti.clone()),
+                refresh_expires_at: Some(now + Duration::seconds(refresh_exp_seconds)),
+                last_used_at: Some(now),
+                ..Default::default()
+            },
+        );
+        tx.commit().await.map_err(|err| match err {
+            Error::Conflict(_) => AuthError::TokenRevoked,
+            err => err.into(),
+        })?;
         Ok(new_refresh_jti)
     }
 
@@ -0,0 +1,263 @@
+use std::sync::Arc;
+
+use axum::Router;
+use axum::http::StatusCode;
+use merak_core::testing::{TestClient, TestDb};
+use merak_core::{Error, Model};
+use serde_json::{Value, json};
+
+use merak::common::code;
+use merak::models::{self, auth::AuthSession, auth::User, auth::UserInput};
+use merak::routes::auth::{self, AuthState};
+use merak::services::auth::AuthService;
+use merak::services::error::AuthError;
+use merak::services::jwt::JwtService;
+
+async fn setup() -> (TestDb, TestClient) {
+    let db = TestDb::with_schema(models::schema()).await.unwrap();
+    let state = AuthState {
+        db: Arc::new(db.client().clone()),
+        auth_service: Arc::new(AuthService::default()),
+    };
+    let (router, _) = auth::routes().with_state(state).split_for_parts();
+    let client = TestClient::new(Router::new().nest("/auth", router));
+    (db, client)
+}
+
+async fn register(client: &TestClient, username: &str) -> Value {
+    let response = client
+        .post("/auth/register")
+        .json(&json!({
+            "username": username,
+            "email": format!("{}@example.com", username),
+            "password": "Password123",
+        }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body: Value = response.json();
+    assert_eq!(body["code"], code::CODE_OK);
+    body["data"].clone()
+}
+
+#[tokio::test]
+async fn register_login_refresh_logout() {
+    let (db, client) = setup().await;
+
+    let registered = register(&client, "merak").await;
+    assert_eq!(registered["user"]["username"], "merak");
+    let user_id = registered["user"]["id"].as_str().unwrap().to_string();
+
+    let response = client
+        .post("/auth/login")
+        .json(&json!({ "identifier": "merak@example.com", "password": "Password123" }))
+        .send()
+        .await;
+    let body: Value = response.json();
+    assert_eq!(body["code"], code::CODE_OK);
+    let access_token = body["data"]["tokens"]["access_token"].as_str().unwrap();
+    let refresh_token = body["data"]["tokens"]["refresh_token"].as_str().unwrap();
+    assert_eq!(AuthSession::objects(&db).query().count().await.unwrap(), 2);
+
+    let me: Value = client
+        .get("/auth/me")
+        .bearer(access_token)
+        .send()
+        .await
+        .json();
+    assert_eq!(me["data"]["id"], user_id.as_str());
+    assert_eq!(me["data"]["email"], "merak@example.com");
+
+    let refreshed: Value = client
+        .post("/auth/refresh")
+        .json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .json();
+    assert_eq!(refreshed["code"], code::CODE_OK);
+    let access_token = refreshed["data"]["tokens"]["access_token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    // The rotated refresh token cannot be used again
+    let reused: Value = client
+        .post("/auth/refresh")
+        .json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .json();
+    assert_eq!(reused["code"], code::auth::TOKEN_INVALID);
+
+    let logout: Value = client
+        .post("/auth/logout")
+        .bearer(&access_token)
+        .send()
+        .await
+        .json();
+    assert_eq!(logout["code"], code::CODE_OK);
+    assert_eq!(AuthSession::objects(&db).query().count().await.unwrap(), 1);
+
+    let me: Value = client
+        .get("/auth/me")
+        .bearer(&access_token)
+        .send()
+        .await
+        .json();
+    assert_eq!(me["code"], code::auth::SESSION_INVALID);
+}
+
+#[tokio::test]
+async fn register_rejects_duplicates_and_weak_passwords() {
+    let (db, client) = setup().await;
+    register(&client, "merak").await;
+
+    let duplicate: Value = client
+        .post("/auth/register")
+        .json(&json!({
+            "username": "merak",
+            "email": "other@example.com",
+            "password": "Password123",
+        }))
+        .send()
+        .await
+        .json();
+    assert_eq!(duplicate["code"], code::auth::USER_EXISTS);
+
+    let weak: Value = client
+        .post("/auth/register")
+        .json(&json!({
+            "username": "other",
+            "email": "other@example.com",
+            "password": "password",
+        }))
+        .send()
+        .await
+        .json();
+    assert_eq!(weak["code"], code::auth::WEAK_PASSWORD);
+
+    // The failed registration must not leave a session behind
+    assert_eq!(User::objects(&db).query().count().await.unwrap(), 1);
+    assert_eq!(AuthSession::objects(&db).query().count().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn login_rejects_invalid_credentials() {
+    let (_db, client) = setup().await;
+    register(&client, "merak").await;
+
+    let body: Value = client
+        .post("/auth/login")
+        .json(&json!({ "identifier": "merak", "password": "Password124" }))
+        .send()
+        .await
+        .json();
+    assert_eq!(body["code"], code::auth::INVALID_CREDENTIALS);
+
+    let body: Value = client.get("/auth/me").send().await.json();
+    assert_eq!(body["code"], code::auth::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn list_sessions_in_pages() {
+    let (_db, client) = setup().await;
+    let registered = register(&client, "merak").await;
+    let access_token = registered["tokens"]["access_token"].as_str().unwrap();
+    for _ in 0..2 {
+        let body: Value = client
+            .post("/auth/login")
+            .json(&json!({ "identifier": "merak", "password": "Password123" }))
+            .send()
+            .await
+            .json();
+        assert_eq!(body["code"], code::CODE_OK);
+    }
+    register(&client, "other").await;
+
+    let first: Value = client
+        .get("/auth/sessions?limit=2")
+        .bearer(access_token)
+        .send()
+        .await
+        .json();
+    assert_eq!(first["code"], code::CODE_OK);
+    assert_eq!(first["items"].as_array().unwrap().len(), 2);
+    assert_eq!(first["has_more"], true);
+    let cursor = first["next_cursor"].as_str().unwrap();
+
+    let second: Value = client
+        .get(&format!("/auth/sessions?limit=2&cursor={}", cursor))
+        .bearer(access_token)
+        .send()
+        .await
+        .json();
+    assert_eq!(second["items"].as_array().unwrap().len(), 1);
+    assert_eq!(second["has_more"], false);
+    assert!(second["next_cursor"].is_null());
+    // The registration session was used first and comes last
+    let claims = JwtService::default()
+        .verify_access_token(access_token)
+        .unwrap();
+    let session_id = second["items"][0]["id"].as_str().unwrap();
+    assert!(session_id.contains(&claims.sid));
+
+    let invalid: Value = client
+        .get("/auth/sessions?cursor=invalid")
+        .bearer(access_token)
+        .send()
+        .await
+        .json();
+    assert_eq!(invalid["code"], code::auth::INVALID_INPUT);
+}
+
+#[tokio::test]
+async fn auth_service() {
+    let db = TestDb::with_schema(models::schema()).await.unwrap();
+    let service = AuthService::default();
+
+    let (user, tokens) = service
+        .register(
+            &db,
+            "merak".to_string(),
+            "merak@example.com".to_string(),
+            "Password123".to_string(),
+        )
+        .await
+        .unwrap();
+    let claims = service
+        .verify_access_token(&db, &tokens.access_token)
+        .await
+        .unwrap();
+    assert_eq!(claims.sub, user.id.to_string());
+
+    let updated = service
+        .update_password(
+            &db,
+            &claims.sub,
+            "Password123".to_string(),
+            "Password456".to_string(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert_ne!(updated.password_hash, user.password_hash);
+    assert!(matches!(
+        service
+            .login(&db, "merak".to_string(), "Password123".to_string())
+            .await,
+        Err(AuthError::InvalidCredentials)
+    ));
+    service
+        .login(&db, "merak".to_string(), "Password456".to_string())
+        .await
+        .unwrap();
+
+    let invalid = db
+        .fixture::<User>(UserInput {
+            username: "me".to_string(),
+            email: "not an email".to_string(),
+            password_hash: String::new(),
+        })
+        .await;
+    assert!(matches!(invalid, Err(Error::Invalid(_))));
+}
@@ -0,0 +1,82 @@
+use merak_core::seed::SeedError;
+use merak_core::testing::TestDb;
+use merak_core::{Factory, Fixtures, Model};
+use serde_json::json;
+
+use merak::models::{self, auth::AuthSession, auth::User};
+use merak::services::auth::AuthService;
+
+#[tokio::test]
+async fn factory() {
+    let db = TestDb::with_schema(models::schema()).await.unwrap();
+
+    let admin = User::factory()
+        .with(|user| user.username = "admin".to_string())
+        .create(&db)
+        .await
+        .unwrap();
+    assert_eq!(admin.username, "admin");
+    assert!(admin.email.ends_with("@example.com"));
+
+    let users = User::factory().create_many(&db, 3).await.unwrap();
+    assert_eq!(users.len(), 3);
+    assert_eq!(User::objects(&db).query().count().await.unwrap(), 4);
+}
+
+#[tokio::test]
+async fn seed_demo_fixtures() {
+    let db = TestDb::with_schema(models::schema()).await.unwrap();
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/demo.yaml");
+    let fixtures = Fixtures::from_path(path).unwrap();
+
+    let seeded = models::seeder().load(&db, &fixtures).await.unwrap();
+    assert_eq!(seeded.len(), 3);
+    let alice = seeded.id::<User>("alice").unwrap();
+    let user = User::get_by_id(&db, &alice).await.unwrap().unwrap();
+    assert_eq!(user.email, "alice@example.com");
+
+    // Passwords built by the factory are usable
+    AuthService::default()
+        .login(&db, "alice".to_string(), "Password123".to_string())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn seed_references() {
+    let db = TestDb::with_schema(models::schema()).await.unwrap();
+    let fixtures = Fixtures::from_value(json!({
+        "auth_sessions": {
+            "alice_session": {
+                "user_id": "@alice",
+                "refresh_jti": "jti",
+                "refresh_expires_at": "2030-01-01T00:00:00Z",
+                "last_used_at": "2026-01-01T00:00:00Z",
+            },
+        },
+        "users": {
+            "alice": { "username": "alice" },
+        },
+    }))
+    .unwrap();
+
+    let seeded = models::seeder().load(&db, &fixtures).await.unwrap();
+    let session_id = seeded.id::<AuthSession>("alice_session").unwrap();
+    let session = AuthSession::get_by_id(&db, &session_id)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Some(session.user_id), seeded.id::<User>("alice"));
+
+    let unknown = Fixtures::from_value(json!({ "projects": { "website": {} } })).unwrap();
+    assert!(matches!(
+        models::seeder().load(&db, &unknown).await,
+        Err(SeedError::UnknownTable(table)) if table == "projects"
+    ));
+
+    let invalid = Fixtures::from_value(json!({ "users": { "bob": { "email": "bob" } } })).unwrap();
+    assert!(matches!(
+        models::seeder().load(&db, &invalid).await,
+        Err(SeedError::Model { record, .. }) if record == "bob"
+    ));
+}